@@ -1,6 +1,13 @@
+use polite::blob::{encode_blobs, BlobEncoding};
 use polite::prelude::*;
+use polite::progress::{Phase, ProgressObserver};
+use polite::{to_dataframe_limited, OnLimit};
 use std::env;
 use std::fs;
+use std::sync::Arc;
+
+/// Rows shown by default, so an unfiltered query on a big table doesn't pull it all in.
+const DEFAULT_MAX_ROWS: usize = 10_000;
 
 /// Entrypoint
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -8,13 +15,55 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn run_cli() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
 
     if args.len() < 2 || args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
         print_help();
         return Ok(());
     }
 
+    // Optional: `--blobs hex|base64` encodes blob columns for display
+    let blobs = match args.iter().position(|a| a == "--blobs") {
+        Some(i) => {
+            let encoding = match args.get(i + 1).map(String::as_str) {
+                Some("hex") => BlobEncoding::Hex,
+                Some("base64") => BlobEncoding::Base64,
+                _ => {
+                    eprintln!("Error: --blobs must be followed by 'hex' or 'base64'.");
+                    std::process::exit(1);
+                }
+            };
+            args.drain(i..i + 2);
+            Some(encoding)
+        }
+        None => None,
+    };
+
+    // Optional: `--progress` reports the phases of a read and the rows fetched on stderr
+    let progress = match args.iter().position(|a| a == "--progress") {
+        Some(i) => {
+            args.remove(i);
+            Some(Arc::new(StderrProgress) as Arc<dyn ProgressObserver>)
+        }
+        None => None,
+    };
+
+    // Optional: `--max-rows N` caps the rows read (0 for no limit)
+    let max_rows = match args.iter().position(|a| a == "--max-rows") {
+        Some(i) => {
+            let max_rows = match args.get(i + 1).map(|n| n.parse::<usize>()) {
+                Some(Ok(n)) => n,
+                _ => {
+                    eprintln!("Error: --max-rows must be followed by a number of rows.");
+                    std::process::exit(1);
+                }
+            };
+            args.drain(i..i + 2);
+            max_rows
+        }
+        None => DEFAULT_MAX_ROWS,
+    };
+
     // First arg is SQL (for now we keep it simple)
     let sql = &args[1];
 
@@ -35,7 +84,20 @@ fn run_cli() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     if sql.trim_start().to_uppercase().starts_with("SELECT") {
-        let df = to_dataframe(db_path, &sql)?;
+        let options = ReadOptions {
+            progress,
+            max_rows: (max_rows > 0).then_some(max_rows),
+            on_limit: OnLimit::Truncate,
+            ..Default::default()
+        };
+        let read = to_dataframe_limited(db_path, &sql, &options)?;
+        if read.truncated {
+            eprintln!("Showing the first {max_rows} row(s); pass --max-rows 0 to read them all");
+        }
+        let mut df = read.df;
+        if let Some(encoding) = blobs {
+            df = encode_blobs(&df, encoding)?;
+        }
         println!("{df}");
     } else {
         // For writes, we only need rusqlite anyway
@@ -47,16 +109,43 @@ fn run_cli() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Reports read progress as lines on stderr, leaving stdout for the result.
+struct StderrProgress;
+
+impl ProgressObserver for StderrProgress {
+    fn phase(&self, phase: Phase) {
+        eprintln!("[{phase}]");
+    }
+
+    fn rows_fetched(&self, partition: usize, rows: usize, total: Option<usize>) {
+        match total {
+            Some(total) => eprintln!("partition {partition}: {rows}/{total} rows"),
+            None => eprintln!("partition {partition}: {rows} rows"),
+        }
+    }
+
+    fn wants_totals(&self) -> bool {
+        true
+    }
+}
+
 fn print_help() {
     println!("polite — rusqlite × Polars bridge demo");
     println!();
     println!("USAGE:");
-    println!("    polite <SQL> [DB_PATH]");
+    println!("    polite <SQL> [DB_PATH] [--blobs hex|base64] [--progress] [--max-rows N]");
     println!();
     println!("ARGS:");
     println!("    <SQL>       SQL statement (use @file.sql to read from file)");
     println!("    [DB_PATH]   Path to SQLite database file (defaults to in-memory)");
     println!();
+    println!("OPTIONS:");
+    println!("    --blobs <hex|base64>   Show blob columns as hex or base64 text");
+    println!("    --progress             Report read phases and rows fetched on stderr");
+    println!(
+        "    --max-rows <N>         Read at most N rows (default {DEFAULT_MAX_ROWS}, 0 for all)"
+    );
+    println!();
     println!("EXAMPLES:");
     println!("    polite \"CREATE TABLE t (id INTEGER, name TEXT)\"");
     println!("    polite \"INSERT INTO t VALUES (1, 'Alice')\"");
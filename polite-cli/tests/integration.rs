@@ -72,3 +72,92 @@ fn create_insert_select_file_fixture() {
     let stdout = String::from_utf8_lossy(&output);
     assert!(stdout.contains("Alice"));
 }
+
+/// Tests that `--blobs hex` shows blob columns as hex text
+#[test]
+fn blobs_flag_encodes_blob_columns() {
+    let db = tempfile::NamedTempFile::new().unwrap();
+
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("CREATE TABLE t (b BLOB)")
+        .arg(db.path())
+        .assert()
+        .success();
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("INSERT INTO t VALUES (X'CAFE')")
+        .arg(db.path())
+        .assert()
+        .success();
+
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("SELECT * FROM t")
+        .arg(db.path())
+        .arg("--blobs")
+        .arg("hex")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cafe"));
+}
+
+/// Tests that `--progress` reports phases and rows on stderr, leaving stdout for the result
+#[test]
+fn progress_flag_reports_on_stderr() {
+    let db = tempfile::NamedTempFile::new().unwrap();
+
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("CREATE TABLE t (id INTEGER)")
+        .arg(db.path())
+        .assert()
+        .success();
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("INSERT INTO t VALUES (1), (2)")
+        .arg(db.path())
+        .assert()
+        .success();
+
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("SELECT * FROM t")
+        .arg(db.path())
+        .arg("--progress")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[fetching]").not())
+        .stderr(predicate::str::contains("[fetching]"))
+        .stderr(predicate::str::contains("partition 0: 2/2 rows"));
+}
+
+/// Tests that `--max-rows` truncates the result and says so on stderr
+#[test]
+fn max_rows_flag_truncates() {
+    let db = tempfile::NamedTempFile::new().unwrap();
+
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("CREATE TABLE t (id INTEGER)")
+        .arg(db.path())
+        .assert()
+        .success();
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("INSERT INTO t VALUES (1), (2), (3)")
+        .arg(db.path())
+        .assert()
+        .success();
+
+    assert_cmd::Command::cargo_bin("polite")
+        .unwrap()
+        .arg("SELECT * FROM t")
+        .arg(db.path())
+        .arg("--max-rows")
+        .arg("2")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("shape: (2, 1)"))
+        .stderr(predicate::str::contains("Showing the first 2 row(s)"));
+}
@@ -0,0 +1,63 @@
+// polite/benches/single_pass.rs
+//! Compares the default and single-pass reads of aggregation-heavy queries. Run with
+//! `cargo bench --bench single_pass`.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use polite::prelude::*;
+use tempfile::NamedTempFile;
+
+const ROWS: usize = 200_000;
+
+fn db(rows: usize) -> NamedTempFile {
+    let tmp = NamedTempFile::new().unwrap();
+    let conn = connect_sqlite(tmp.path().to_str()).unwrap();
+    conn.execute_batch(&format!(
+        "CREATE TABLE t (id INTEGER, grp INTEGER, score REAL, note TEXT);
+         WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < {rows})
+         INSERT INTO t SELECT i, i % 100, i * 0.5, CASE WHEN i > 2 THEN 'n' || i END FROM n;"
+    ))
+    .unwrap();
+    tmp
+}
+
+fn aggregations(c: &mut Criterion) {
+    let tmp = db(ROWS);
+    let db_path = tmp.path().to_str().unwrap();
+    let queries = [
+        (
+            "group_by",
+            "SELECT grp, COUNT(*), SUM(score), AVG(id) FROM t GROUP BY grp",
+        ),
+        (
+            "join_aggregate",
+            "SELECT a.grp, COUNT(*) FROM t a JOIN (SELECT grp, MAX(score) AS m FROM t GROUP BY grp) b \
+             ON a.grp = b.grp AND a.score > b.m / 2 GROUP BY a.grp",
+        ),
+        (
+            "top_notes",
+            "SELECT note, COUNT(*) FROM t GROUP BY note ORDER BY 2 DESC LIMIT 10",
+        ),
+    ];
+
+    let mut group = c.benchmark_group("aggregations");
+    group.sample_size(10);
+    for (name, sql) in queries {
+        for single_pass in [false, true] {
+            let options = ReadOptions {
+                single_pass,
+                ..Default::default()
+            };
+            let read = if single_pass {
+                "single_pass"
+            } else {
+                "default"
+            };
+            group.bench_with_input(BenchmarkId::new(name, read), sql, |b, sql| {
+                b.iter(|| to_dataframe_with_options(db_path, sql, &options).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, aggregations);
+criterion_main!(benches);
@@ -0,0 +1,136 @@
+//! Blob columns: UUIDs stored as 16-byte blobs, and text encodings for display.
+
+use crate::PoliteError;
+use polars::prelude::*;
+
+/// Text encoding used to display `Binary` columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobEncoding {
+    /// Lowercase hexadecimal, two characters per byte.
+    Hex,
+    /// Standard base64 with `=` padding.
+    Base64,
+}
+
+/// Replace every `Binary` column in `df` with its text encoding, e.g. for printing.
+pub fn encode_blobs(df: &DataFrame, encoding: BlobEncoding) -> Result<DataFrame, PoliteError> {
+    let mut out = df.clone();
+    for column in df.get_columns() {
+        if column.dtype() != &DataType::Binary {
+            continue;
+        }
+        let encoded: StringChunked = column
+            .binary()
+            .map_err(|e| PoliteError::DataFrame { source: e })?
+            .iter()
+            .map(|v| {
+                v.map(|bytes| match encoding {
+                    BlobEncoding::Hex => to_hex(bytes),
+                    BlobEncoding::Base64 => to_base64(bytes),
+                })
+            })
+            .collect();
+        out.replace(column.name(), encoded)
+            .map_err(|e| PoliteError::DataFrame { source: e })?;
+    }
+    Ok(out)
+}
+
+/// Whether a declared column type marks the column as holding 16-byte UUIDs.
+pub(crate) fn is_uuid_decl(decl_type: Option<&str>) -> bool {
+    decl_type.is_some_and(|d| {
+        let d: String = d.chars().filter(|c| !c.is_whitespace()).collect();
+        d.eq_ignore_ascii_case("uuid") || d.eq_ignore_ascii_case("blob(16)")
+    })
+}
+
+/// Decode the named `Binary` columns into canonical UUID strings.
+pub(crate) fn decode_uuid_columns(
+    mut df: DataFrame,
+    columns: &[String],
+) -> Result<DataFrame, PoliteError> {
+    for name in columns {
+        let column = df
+            .column(name)
+            .map_err(|e| PoliteError::DataFrame { source: e })?;
+        // UUIDs already stored as text need no decoding
+        if column.dtype() != &DataType::Binary {
+            continue;
+        }
+        let decoded = column
+            .binary()
+            .map_err(|e| PoliteError::DataFrame { source: e })?
+            .iter()
+            .enumerate()
+            .map(|(row, v)| match v {
+                Some(bytes) => {
+                    format_uuid(bytes)
+                        .map(Some)
+                        .ok_or_else(|| PoliteError::InvalidUuid {
+                            column: name.clone(),
+                            row,
+                            value: to_hex(bytes),
+                        })
+                }
+                None => Ok(None),
+            })
+            .collect::<Result<StringChunked, _>>()?;
+        df.replace(name, decoded)
+            .map_err(|e| PoliteError::DataFrame { source: e })?;
+    }
+    Ok(df)
+}
+
+/// Format 16 bytes as a hyphenated lowercase UUID.
+pub(crate) fn format_uuid(bytes: &[u8]) -> Option<String> {
+    if bytes.len() != 16 {
+        return None;
+    }
+    let hex = to_hex(bytes);
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+/// Parse a UUID string, with or without hyphens, into its 16 bytes.
+pub(crate) fn parse_uuid(s: &str) -> Option<[u8; 16]> {
+    let hex: Vec<u8> = s.bytes().filter(|&b| b != b'-').collect();
+    // `from_str_radix` would also take a sign, as in `+f`
+    if hex.len() != 32 || !hex.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    let mut bytes = [0u8; 16];
+    for (byte, pair) in bytes.iter_mut().zip(hex.chunks(2)) {
+        let pair = std::str::from_utf8(pair).ok()?;
+        *byte = u8::from_str_radix(pair, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn to_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
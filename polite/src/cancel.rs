@@ -0,0 +1,127 @@
+//! Cancelling reads that run too long.
+//!
+//! A [`CancellationToken`] passed in [`ReadOptions::cancel`](crate::ReadOptions) can be
+//! cancelled from another thread. Every connection the read uses is registered with it, and
+//! cancelling calls `sqlite3_interrupt` on each of them, so the read stops promptly and
+//! returns [`PoliteError::Cancelled`](crate::PoliteError) with the rows fetched so far.
+
+use rusqlite::{Connection, InterruptHandle};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A handle for cancelling one or more reads. Clones share the same state.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    next_id: AtomicU64,
+    handles: Mutex<Vec<(u64, InterruptHandle)>>,
+    parent: Option<Arc<Inner>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every read using this token, interrupting any statement they are running.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        for (_, handle) in self.inner.handles.lock().unwrap().iter() {
+            handle.interrupt();
+        }
+    }
+
+    /// Whether this token, or the token it was derived from, has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        let mut inner = Some(&self.inner);
+        while let Some(i) = inner {
+            if i.cancelled.load(Ordering::SeqCst) {
+                return true;
+            }
+            inner = i.parent.as_ref();
+        }
+        false
+    }
+
+    /// A token cancelled along with this one, which can also be cancelled on its own.
+    pub(crate) fn child(&self) -> Self {
+        CancellationToken {
+            inner: Arc::new(Inner {
+                parent: Some(Arc::clone(&self.inner)),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Interrupt `conn` when this token (or its parent) is cancelled, until the returned
+    /// registration is dropped.
+    pub(crate) fn register(&self, conn: &Connection) -> Registration {
+        let mut entries = vec![];
+        let mut inner = Some(&self.inner);
+        while let Some(i) = inner {
+            let id = i.next_id.fetch_add(1, Ordering::Relaxed);
+            i.handles
+                .lock()
+                .unwrap()
+                .push((id, conn.get_interrupt_handle()));
+            entries.push((Arc::clone(i), id));
+            inner = i.parent.as_ref();
+        }
+        if self.is_cancelled() {
+            conn.get_interrupt_handle().interrupt();
+        }
+        Registration { entries }
+    }
+
+    /// Cancel this token once `timeout` elapses, unless the returned timer is dropped first.
+    pub(crate) fn cancel_after(&self, timeout: Duration) -> Timer {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let token = self.clone();
+        std::thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(timeout) {
+                token.cancel();
+            }
+        });
+        Timer { _stop: stop }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// A connection registered with a [`CancellationToken`], unregistered on drop.
+pub(crate) struct Registration {
+    entries: Vec<(Arc<Inner>, u64)>,
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registration").finish_non_exhaustive()
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        for (inner, id) in &self.entries {
+            inner.handles.lock().unwrap().retain(|(i, _)| i != id);
+        }
+    }
+}
+
+/// A pending timeout, disarmed on drop.
+pub(crate) struct Timer {
+    _stop: Sender<()>,
+}
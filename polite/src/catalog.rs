@@ -0,0 +1,138 @@
+//! Several database files queried together under aliases.
+//!
+//! A [`Catalog`] names a main database and attaches other files to it, e.g. `raw`, `staging`
+//! and `marts`. Every connection it hands out, including the ones its reads open, has them all
+//! attached, so a query can join across them and a write can name a table as `staging.events`.
+//! Aliases attached read-only refuse writes.
+//!
+//! ```rust
+//! use polite::catalog::Catalog;
+//! use polars::prelude::*;
+//! # let dir = tempfile::tempdir().unwrap();
+//! # let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+//! # let (main, raw, staging) = (path("main.db"), path("raw.db"), path("staging.db"));
+//! # polite::connect_sqlite(Some(&raw)).unwrap()
+//! #     .execute_batch("CREATE TABLE users (id INTEGER, name TEXT); INSERT INTO users VALUES (1, 'Alice')")
+//! #     .unwrap();
+//!
+//! let catalog = Catalog::new(&main)
+//!     .attach_read_only("raw", &raw)
+//!     .attach("staging", &staging);
+//!
+//! let events = df! { "user_id" => [1i64, 1], "kind" => ["open", "close"] }.unwrap();
+//! catalog.from_dataframe("staging.events", &events).unwrap();
+//!
+//! let joined = catalog
+//!     .to_dataframe(
+//!         "SELECT u.name, e.kind FROM raw.users u JOIN staging.events e ON e.user_id = u.id",
+//!     )
+//!     .unwrap();
+//! assert_eq!(joined.height(), 2);
+//! ```
+
+use crate::dataframe::{from_dataframe_with_options, to_dataframe_with_options};
+use crate::db::connect_sqlite_with_options;
+use crate::options::{Attachment, ConnectOptions, ReadOptions, WriteOptions};
+use crate::PoliteError;
+use polars::prelude::DataFrame;
+use rusqlite::Connection;
+
+/// A main database with other databases attached to it under aliases.
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    /// Path of the main database, which unqualified table names refer to.
+    pub main: String,
+    /// The attached databases, in the order they are attached.
+    pub attached: Vec<Attachment>,
+}
+
+impl Catalog {
+    pub fn new(main: &str) -> Self {
+        Catalog {
+            main: main.to_string(),
+            attached: vec![],
+        }
+    }
+
+    /// Attach the database at `path` as `alias`.
+    pub fn attach(mut self, alias: &str, path: &str) -> Self {
+        self.attached.push(Attachment {
+            alias: alias.to_string(),
+            path: path.to_string(),
+            read_only: false,
+        });
+        self
+    }
+
+    /// Attach the database at `path` as `alias`, refusing writes to it.
+    pub fn attach_read_only(mut self, alias: &str, path: &str) -> Self {
+        self.attached.push(Attachment {
+            alias: alias.to_string(),
+            path: path.to_string(),
+            read_only: true,
+        });
+        self
+    }
+
+    /// `options` with the catalog's databases attached after any it already attaches.
+    pub fn connect_options(&self, options: &ConnectOptions) -> ConnectOptions {
+        let mut options = options.clone();
+        options.attached.extend(self.attached.iter().cloned());
+        options
+    }
+
+    /// Open the main database with every alias attached.
+    pub fn connect(&self) -> Result<Connection, PoliteError> {
+        self.connect_with_options(&ConnectOptions::default())
+    }
+
+    /// Open the main database with `options`, and every alias attached.
+    pub fn connect_with_options(
+        &self,
+        options: &ConnectOptions,
+    ) -> Result<Connection, PoliteError> {
+        connect_sqlite_with_options(Some(&self.main), &self.connect_options(options))
+    }
+
+    /// Read a query over the catalog's databases into a DataFrame.
+    pub fn to_dataframe(&self, sql: &str) -> Result<DataFrame, PoliteError> {
+        self.to_dataframe_with_options(sql, &ReadOptions::default())
+    }
+
+    /// Read a query over the catalog's databases, with every connection the read opens having
+    /// them attached.
+    pub fn to_dataframe_with_options(
+        &self,
+        sql: &str,
+        options: &ReadOptions,
+    ) -> Result<DataFrame, PoliteError> {
+        let options = ReadOptions {
+            connect: self.connect_options(&options.connect),
+            ..options.clone()
+        };
+        to_dataframe_with_options(&self.main, sql, &options)
+    }
+
+    /// Write a DataFrame to `table`, which may be qualified by an alias as `alias.table`.
+    pub fn from_dataframe(&self, table: &str, df: &DataFrame) -> Result<(), PoliteError> {
+        self.from_dataframe_with_options(
+            table,
+            df,
+            &ConnectOptions::default(),
+            &WriteOptions::default(),
+        )
+    }
+
+    /// Like [`Catalog::from_dataframe`], opening the main database with `connect` and every
+    /// alias attached, and controlling the write with [`WriteOptions`].
+    pub fn from_dataframe_with_options(
+        &self,
+        table: &str,
+        df: &DataFrame,
+        connect: &ConnectOptions,
+        options: &WriteOptions,
+    ) -> Result<(), PoliteError> {
+        let conn = self.connect_with_options(connect)?;
+        from_dataframe_with_options(&conn, table, df, options)
+    }
+}
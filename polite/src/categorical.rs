@@ -0,0 +1,74 @@
+//! Categorical and Enum dtypes for text columns on read.
+
+use crate::options::ReadOptions;
+use polars::prelude::*;
+use polars_arrow::array::Utf8ViewArray;
+
+/// Text columns the destination should build as dictionary arrays, or `None` for all of them.
+pub(crate) fn dictionary_columns(options: &ReadOptions) -> Option<Vec<String>> {
+    if options.categorical_text {
+        return None;
+    }
+    Some(
+        options
+            .categorical_columns
+            .iter()
+            .chain(options.enum_columns.iter().map(|(name, _)| name))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Give each column the Categorical or Enum dtype requested by `options`. Columns already
+/// dictionary-encoded on read are left as they are; the rest (e.g. in an empty result, or
+/// detected by `categorical_threshold`) are cast. The threshold is judged on the column as
+/// read, so its `String` values were all held at once before the cast.
+pub(crate) fn apply_categoricals(
+    mut df: DataFrame,
+    options: &ReadOptions,
+) -> PolarsResult<DataFrame> {
+    let categorical = DataType::Categorical(None, Default::default());
+    let names: Vec<PlSmallStr> = df.get_column_names_owned();
+    for name in names {
+        let column = df.column(&name)?;
+        let target = match options
+            .enum_columns
+            .iter()
+            .find(|(n, _)| n == name.as_str())
+        {
+            Some((_, categories)) => {
+                create_enum_dtype(Utf8ViewArray::from_slice_values(categories))
+            }
+            None if column.dtype() != &DataType::String => continue,
+            None if options.categorical_text
+                || options
+                    .categorical_columns
+                    .iter()
+                    .any(|c| c == name.as_str()) =>
+            {
+                categorical.clone()
+            }
+            None => match options.categorical_threshold {
+                Some(threshold) if distinct_ratio(column)? <= threshold => categorical.clone(),
+                _ => continue,
+            },
+        };
+        if column.dtype() == &target {
+            continue;
+        }
+        // Strict, so values outside an Enum's categories are an error rather than null
+        let cast = column.strict_cast(&target)?;
+        df.replace(&name, cast.take_materialized_series())?;
+    }
+    Ok(df)
+}
+
+/// Distinct non-null values per non-null value; above 1 (never matching) if all are null.
+fn distinct_ratio(column: &Column) -> PolarsResult<f64> {
+    let non_null = column.len() - column.null_count();
+    if non_null == 0 {
+        return Ok(f64::INFINITY);
+    }
+    let distinct = column.drop_nulls().n_unique()?;
+    Ok(distinct as f64 / non_null as f64)
+}
@@ -4,13 +4,7 @@ use itertools::Itertools;
 use log::debug;
 use rayon::prelude::*;
 use std::marker::PhantomData;
-
-pub fn set_global_num_thread(num: usize) {
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(num)
-        .build_global()
-        .unwrap();
-}
+use std::sync::Arc;
 
 /// The iterator that returns arrow in `RecordBatch`
 pub struct ArrowBatchIter<S, TP>
@@ -32,6 +26,7 @@ where
     dorder: DataOrder,
     src_schema: Vec<S::TypeSystem>,
     dst_schema: Vec<ArrowStreamTypeSystem>,
+    pool: Option<Arc<rayon::ThreadPool>>,
     _phantom: PhantomData<TP>,
 }
 
@@ -52,7 +47,7 @@ where
         queries: &[CXQuery<String>],
     ) -> Result<Self, TP::Error> {
         let dispatcher = Dispatcher::<_, _, TP>::new(src, &mut dst, queries, origin_query);
-        let (dorder, src_parts, dst_parts, src_schema, dst_schema) = dispatcher.prepare()?;
+        let (dorder, src_parts, dst_parts, src_schema, dst_schema, _) = dispatcher.prepare()?;
 
         Ok(Self {
             dst,
@@ -61,16 +56,23 @@ where
             dorder,
             src_schema,
             dst_schema,
+            pool: None,
             _phantom: PhantomData,
         })
     }
 
+    /// Fetch the partitions on `pool` rather than rayon's global pool.
+    pub fn set_thread_pool(&mut self, pool: Option<Arc<rayon::ThreadPool>>) {
+        self.pool = pool;
+    }
+
     fn run(&mut self) {
         let src_schema = self.src_schema.clone();
         let dst_schema = self.dst_schema.clone();
         let src_partitions = self.src_parts.take().unwrap();
         let dst_partitions = self.dst_parts.take().unwrap();
         let dorder = self.dorder;
+        let pool = self.pool.clone();
 
         std::thread::spawn(move || -> Result<(), TP::Error> {
             let schemas: Vec<_> = src_schema
@@ -81,53 +83,59 @@ where
 
             debug!("Start writing");
             // parse and write
-            dst_partitions
-                .into_par_iter()
-                .zip_eq(src_partitions)
-                .enumerate()
-                .try_for_each(|(i, (mut dst, mut src))| -> Result<(), TP::Error> {
-                    let mut parser = src.parser()?;
-
-                    match dorder {
-                        DataOrder::RowMajor => loop {
-                            let (n, is_last) = parser.fetch_next()?;
-                            dst.aquire_row(n)?;
-                            for _ in 0..n {
+            let write = || {
+                dst_partitions
+                    .into_par_iter()
+                    .zip_eq(src_partitions)
+                    .enumerate()
+                    .try_for_each(|(i, (mut dst, mut src))| -> Result<(), TP::Error> {
+                        let mut parser = src.parser()?;
+
+                        match dorder {
+                            DataOrder::RowMajor => loop {
+                                let (n, is_last) = parser.fetch_next()?;
+                                dst.aquire_row(n)?;
+                                for _ in 0..n {
+                                    #[allow(clippy::needless_range_loop)]
+                                    for col in 0..dst.ncols() {
+                                        {
+                                            let (s1, s2) = schemas[col];
+                                            TP::process(s1, s2, &mut parser, &mut dst)?;
+                                        }
+                                    }
+                                }
+                                if is_last {
+                                    break;
+                                }
+                            },
+                            DataOrder::ColumnMajor => loop {
+                                let (n, is_last) = parser.fetch_next()?;
+                                dst.aquire_row(n)?;
                                 #[allow(clippy::needless_range_loop)]
                                 for col in 0..dst.ncols() {
-                                    {
-                                        let (s1, s2) = schemas[col];
-                                        TP::process(s1, s2, &mut parser, &mut dst)?;
+                                    for _ in 0..n {
+                                        {
+                                            let (s1, s2) = schemas[col];
+                                            TP::process(s1, s2, &mut parser, &mut dst)?;
+                                        }
                                     }
                                 }
-                            }
-                            if is_last {
-                                break;
-                            }
-                        },
-                        DataOrder::ColumnMajor => loop {
-                            let (n, is_last) = parser.fetch_next()?;
-                            dst.aquire_row(n)?;
-                            #[allow(clippy::needless_range_loop)]
-                            for col in 0..dst.ncols() {
-                                for _ in 0..n {
-                                    {
-                                        let (s1, s2) = schemas[col];
-                                        TP::process(s1, s2, &mut parser, &mut dst)?;
-                                    }
+                                if is_last {
+                                    break;
                                 }
-                            }
-                            if is_last {
-                                break;
-                            }
-                        },
-                    }
-
-                    debug!("Finalize partition {}", i);
-                    dst.finalize()?;
-                    debug!("Partition {} finished", i);
-                    Ok(())
-                })?;
+                            },
+                        }
+
+                        debug!("Finalize partition {}", i);
+                        dst.finalize()?;
+                        debug!("Partition {} finished", i);
+                        Ok(())
+                    })
+            };
+            match pool {
+                Some(pool) => pool.install(write)?,
+                None => write()?,
+            }
 
             debug!("Writing finished");
 
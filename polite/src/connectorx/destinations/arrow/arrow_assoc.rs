@@ -3,20 +3,28 @@ use super::{
     typesystem::{DateTimeWrapperMicro, NaiveDateTimeWrapperMicro, NaiveTimeWrapperMicro},
 };
 use crate::connectorx::{
-    constants::{DEFAULT_ARROW_DECIMAL, DEFAULT_ARROW_DECIMAL_SCALE, SECONDS_IN_DAY},
+    constants::{
+        DEFAULT_ARROW_DECIMAL, DEFAULT_ARROW_DECIMAL_PRECISION, DEFAULT_ARROW_DECIMAL_SCALE,
+        SECONDS_IN_DAY,
+    },
     utils::decimal_to_i128,
 };
 use arrow::array::{
-    ArrayBuilder, BooleanBuilder, Date32Builder, Decimal128Builder, Float32Builder, Float64Builder,
-    Int16Builder, Int32Builder, Int64Builder, LargeBinaryBuilder, LargeListBuilder, StringBuilder,
-    Time64MicrosecondBuilder, Time64NanosecondBuilder, TimestampMicrosecondBuilder,
-    TimestampNanosecondBuilder, UInt16Builder, UInt32Builder, UInt64Builder,
+    ArrayBuilder, ArrayRef, BooleanBuilder, Date32Builder, Decimal128Builder, Float32Builder,
+    Float64Builder, Int16Builder, Int32Builder, Int64Builder, Int8Builder, LargeBinaryBuilder,
+    LargeListBuilder, StringBuilder, StringDictionaryBuilder, Time64MicrosecondBuilder,
+    Time64NanosecondBuilder, TimestampMicrosecondBuilder, TimestampNanosecondBuilder,
+    UInt16Builder, UInt32Builder, UInt64Builder, UInt8Builder,
 };
 use arrow::datatypes::Field;
-use arrow::datatypes::{DataType as ArrowDataType, TimeUnit};
+use arrow::datatypes::{
+    DataType as ArrowDataType, Decimal128Type, DecimalType, TimeUnit, UInt32Type,
+};
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
 use fehler::throws;
 use rust_decimal::Decimal;
+use std::any::Any;
+use std::sync::Arc;
 
 /// Associate arrow builder with native type
 pub trait ArrowAssoc {
@@ -65,9 +73,11 @@ macro_rules! impl_arrow_assoc {
     };
 }
 
+impl_arrow_assoc!(u8, ArrowDataType::UInt8, UInt8Builder);
 impl_arrow_assoc!(u16, ArrowDataType::UInt16, UInt16Builder);
 impl_arrow_assoc!(u32, ArrowDataType::UInt32, UInt32Builder);
 impl_arrow_assoc!(u64, ArrowDataType::UInt64, UInt64Builder);
+impl_arrow_assoc!(i8, ArrowDataType::Int8, Int8Builder);
 impl_arrow_assoc!(i16, ArrowDataType::Int16, Int16Builder);
 impl_arrow_assoc!(i32, ArrowDataType::Int32, Int32Builder);
 impl_arrow_assoc!(i64, ArrowDataType::Int64, Int64Builder);
@@ -75,16 +85,76 @@ impl_arrow_assoc!(f32, ArrowDataType::Float32, Float32Builder);
 impl_arrow_assoc!(f64, ArrowDataType::Float64, Float64Builder);
 impl_arrow_assoc!(bool, ArrowDataType::Boolean, BooleanBuilder);
 
+/// A [`Decimal128Builder`] that remembers the scale values are rescaled to on append,
+/// so columns can carry their declared precision and scale rather than the default.
+pub struct DecimalBuilder {
+    inner: Decimal128Builder,
+    precision: u8,
+    scale: i8,
+}
+
+impl DecimalBuilder {
+    pub fn new(nrows: usize, precision: u8, scale: i8) -> Self {
+        DecimalBuilder {
+            inner: Decimal128Builder::with_capacity(nrows)
+                .with_data_type(ArrowDataType::Decimal128(precision, scale)),
+            precision,
+            scale,
+        }
+    }
+
+    fn append_option(&mut self, value: Option<Decimal>) -> Result<()> {
+        match value {
+            Some(v) => {
+                let v = decimal_to_i128(v, self.scale as u32)?;
+                Decimal128Type::validate_decimal_precision(v, self.precision)?;
+                self.inner.append_value(v);
+            }
+            None => self.inner.append_null(),
+        }
+        Ok(())
+    }
+}
+
+impl ArrayBuilder for DecimalBuilder {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        Arc::new(self.inner.finish())
+    }
+
+    fn finish_cloned(&self) -> ArrayRef {
+        Arc::new(self.inner.finish_cloned())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_box_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
 impl ArrowAssoc for Decimal {
-    type Builder = Decimal128Builder;
+    type Builder = DecimalBuilder;
 
     fn builder(nrows: usize) -> Self::Builder {
-        Decimal128Builder::with_capacity(nrows).with_data_type(DEFAULT_ARROW_DECIMAL)
+        DecimalBuilder::new(
+            nrows,
+            DEFAULT_ARROW_DECIMAL_PRECISION,
+            DEFAULT_ARROW_DECIMAL_SCALE,
+        )
     }
 
     fn append(builder: &mut Self::Builder, value: Self) -> Result<()> {
-        builder.append_value(decimal_to_i128(value, DEFAULT_ARROW_DECIMAL_SCALE as u32)?);
-        Ok(())
+        builder.append_option(Some(value))
     }
 
     fn field(header: &str) -> Field {
@@ -93,21 +163,18 @@ impl ArrowAssoc for Decimal {
 }
 
 impl ArrowAssoc for Option<Decimal> {
-    type Builder = Decimal128Builder;
+    type Builder = DecimalBuilder;
 
     fn builder(nrows: usize) -> Self::Builder {
-        Decimal128Builder::with_capacity(nrows).with_data_type(DEFAULT_ARROW_DECIMAL)
+        DecimalBuilder::new(
+            nrows,
+            DEFAULT_ARROW_DECIMAL_PRECISION,
+            DEFAULT_ARROW_DECIMAL_SCALE,
+        )
     }
 
     fn append(builder: &mut Self::Builder, value: Self) -> Result<()> {
-        match value {
-            Some(v) => builder.append_option(Some(decimal_to_i128(
-                v,
-                DEFAULT_ARROW_DECIMAL_SCALE as u32,
-            )?)),
-            None => builder.append_null(),
-        }
-        Ok(())
+        builder.append_option(value)
     }
 
     fn field(header: &str) -> Field {
@@ -152,16 +219,75 @@ impl ArrowAssoc for Option<&str> {
     }
 }
 
+/// A text builder producing either a plain `Utf8` array or, for low-cardinality columns, a
+/// `Dictionary(UInt32, Utf8)` array that Polars imports as `Categorical`.
+pub enum TextBuilder {
+    Plain(StringBuilder),
+    Dictionary(StringDictionaryBuilder<UInt32Type>),
+}
+
+impl TextBuilder {
+    pub fn dictionary(nrows: usize) -> Self {
+        TextBuilder::Dictionary(StringDictionaryBuilder::with_capacity(nrows, 256, 1024))
+    }
+
+    fn append_option(&mut self, value: Option<&str>) -> Result<()> {
+        match (self, value) {
+            (TextBuilder::Plain(b), Some(v)) => b.append_value(v),
+            (TextBuilder::Plain(b), None) => b.append_null(),
+            (TextBuilder::Dictionary(b), Some(v)) => {
+                b.append(v)?;
+            }
+            (TextBuilder::Dictionary(b), None) => b.append_null(),
+        }
+        Ok(())
+    }
+}
+
+impl ArrayBuilder for TextBuilder {
+    fn len(&self) -> usize {
+        match self {
+            TextBuilder::Plain(b) => b.len(),
+            TextBuilder::Dictionary(b) => b.len(),
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            TextBuilder::Plain(b) => Arc::new(b.finish()),
+            TextBuilder::Dictionary(b) => Arc::new(b.finish()),
+        }
+    }
+
+    fn finish_cloned(&self) -> ArrayRef {
+        match self {
+            TextBuilder::Plain(b) => Arc::new(b.finish_cloned()),
+            TextBuilder::Dictionary(b) => Arc::new(b.finish_cloned()),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_box_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
 impl ArrowAssoc for String {
-    type Builder = StringBuilder;
+    type Builder = TextBuilder;
 
     fn builder(nrows: usize) -> Self::Builder {
-        StringBuilder::with_capacity(1024, nrows)
+        TextBuilder::Plain(StringBuilder::with_capacity(1024, nrows))
     }
 
-    #[throws(ArrowDestinationError)]
-    fn append(builder: &mut Self::Builder, value: String) {
-        builder.append_value(value.as_str());
+    fn append(builder: &mut Self::Builder, value: String) -> Result<()> {
+        builder.append_option(Some(value.as_str()))
     }
 
     fn field(header: &str) -> Field {
@@ -170,18 +296,14 @@ impl ArrowAssoc for String {
 }
 
 impl ArrowAssoc for Option<String> {
-    type Builder = StringBuilder;
+    type Builder = TextBuilder;
 
     fn builder(nrows: usize) -> Self::Builder {
-        StringBuilder::with_capacity(1024, nrows)
+        TextBuilder::Plain(StringBuilder::with_capacity(1024, nrows))
     }
 
-    #[throws(ArrowDestinationError)]
-    fn append(builder: &mut Self::Builder, value: Self) {
-        match value {
-            Some(s) => builder.append_value(s.as_str()),
-            None => builder.append_null(),
-        }
+    fn append(builder: &mut Self::Builder, value: Self) -> Result<()> {
+        builder.append_option(value.as_deref())
     }
 
     fn field(header: &str) -> Field {
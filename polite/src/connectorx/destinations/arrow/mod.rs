@@ -11,9 +11,13 @@ use super::{Consume, Destination, DestinationPartition};
 use crate::connectorx::constants::RECORD_BATCH_SIZE;
 use crate::connectorx::data_order::DataOrder;
 use crate::connectorx::typesystem::{Realize, TypeAssoc, TypeSystem};
+use crate::limit::{ReadBudget, BYTE_LIMIT_BATCH_SIZE};
 use anyhow::anyhow;
-use arrow::{datatypes::Schema, record_batch::RecordBatch};
-use arrow_assoc::ArrowAssoc;
+use arrow::{
+    datatypes::{DataType as ArrowDataType, Field, Schema},
+    record_batch::RecordBatch,
+};
+use arrow_assoc::{ArrowAssoc, DecimalBuilder, TextBuilder};
 use fehler::{throw, throws};
 use funcs::{FFinishBuilder, FNewBuilder, FNewField};
 use itertools::Itertools;
@@ -40,6 +44,9 @@ pub struct ArrowDestination {
     data: Arc<Mutex<Vec<RecordBatch>>>,
     arrow_schema: Arc<Schema>,
     batch_size: usize,
+    decimal_types: Vec<Option<(u8, i8)>>,
+    dictionary_columns: Option<Vec<String>>,
+    budget: Option<Arc<ReadBudget>>,
 }
 
 impl Default for ArrowDestination {
@@ -50,6 +57,9 @@ impl Default for ArrowDestination {
             data: Arc::new(Mutex::new(vec![])),
             arrow_schema: Arc::new(Schema::empty()),
             batch_size: RECORD_BATCH_SIZE,
+            decimal_types: vec![],
+            dictionary_columns: Some(vec![]),
+            budget: None,
         }
     }
 }
@@ -66,7 +76,31 @@ impl ArrowDestination {
             data: Arc::new(Mutex::new(vec![])),
             arrow_schema: Arc::new(Schema::empty()),
             batch_size,
+            decimal_types: vec![],
+            dictionary_columns: Some(vec![]),
+            budget: None,
+        }
+    }
+
+    /// Precision and scale for each decimal column, in place of the default
+    /// `Decimal128(38, 10)`. Must be set before allocation.
+    pub fn set_decimal_types(&mut self, decimal_types: Vec<Option<(u8, i8)>>) {
+        self.decimal_types = decimal_types;
+    }
+
+    /// Text columns to build as dictionary arrays (Polars `Categorical`), or `None` for every
+    /// text column. Must be set before allocation.
+    pub fn set_dictionary_columns(&mut self, columns: Option<Vec<String>>) {
+        self.dictionary_columns = columns;
+    }
+
+    /// Count the bytes of each flushed batch against `budget`, flushing smaller batches when
+    /// it has a byte limit. Must be set before partitioning.
+    pub fn set_budget(&mut self, budget: Option<Arc<ReadBudget>>) {
+        if budget.as_ref().is_some_and(|b| b.has_byte_limit()) {
+            self.batch_size = self.batch_size.min(BYTE_LIMIT_BATCH_SIZE);
         }
+        self.budget = budget;
     }
 }
 
@@ -100,7 +134,33 @@ impl Destination for ArrowDestination {
             .schema
             .iter()
             .zip_eq(&self.names)
-            .map(|(&dt, h)| Ok(Realize::<FNewField>::realize(dt)?(h.as_str())))
+            .enumerate()
+            .map(|(i, (&dt, h))| {
+                let field = Realize::<FNewField>::realize(dt)?(h.as_str());
+                Ok(match (dt, self.decimal_types.get(i)) {
+                    (ArrowTypeSystem::Decimal(_), Some(&Some((precision, scale)))) => Field::new(
+                        field.name(),
+                        ArrowDataType::Decimal128(precision, scale),
+                        field.is_nullable(),
+                    ),
+                    (ArrowTypeSystem::LargeUtf8(_), _)
+                        if self
+                            .dictionary_columns
+                            .as_ref()
+                            .is_none_or(|columns| columns.iter().any(|c| c == h)) =>
+                    {
+                        Field::new(
+                            field.name(),
+                            ArrowDataType::Dictionary(
+                                Box::new(ArrowDataType::UInt32),
+                                Box::new(ArrowDataType::Utf8),
+                            ),
+                            field.is_nullable(),
+                        )
+                    }
+                    _ => field,
+                })
+            })
             .collect::<Result<Vec<_>>>()?;
         self.arrow_schema = Arc::new(Schema::new(fields));
     }
@@ -114,6 +174,7 @@ impl Destination for ArrowDestination {
                 Arc::clone(&self.data),
                 Arc::clone(&self.arrow_schema),
                 self.batch_size,
+                self.budget.clone(),
             )?);
         }
         partitions
@@ -217,6 +278,7 @@ pub struct ArrowPartitionWriter {
     data: Arc<Mutex<Vec<RecordBatch>>>,
     arrow_schema: Arc<Schema>,
     batch_size: usize,
+    budget: Option<Arc<ReadBudget>>,
 }
 
 // unsafe impl Sync for ArrowPartitionWriter {}
@@ -228,6 +290,7 @@ impl ArrowPartitionWriter {
         data: Arc<Mutex<Vec<RecordBatch>>>,
         arrow_schema: Arc<Schema>,
         batch_size: usize,
+        budget: Option<Arc<ReadBudget>>,
     ) -> Self {
         let mut pw = ArrowPartitionWriter {
             schema,
@@ -237,6 +300,7 @@ impl ArrowPartitionWriter {
             data,
             arrow_schema,
             batch_size,
+            budget,
         };
         pw.allocate()?;
         pw
@@ -244,11 +308,25 @@ impl ArrowPartitionWriter {
 
     #[throws(ArrowDestinationError)]
     fn allocate(&mut self) {
-        let builders = self
+        let mut builders = self
             .schema
             .iter()
             .map(|dt| Ok(Realize::<FNewBuilder>::realize(*dt)?(self.batch_size)))
             .collect::<Result<Vec<_>>>()?;
+        // decimal builders rescale to the column's own scale rather than the default
+        for (builder, field) in builders.iter_mut().zip(self.arrow_schema.fields()) {
+            if let ArrowDataType::Decimal128(precision, scale) = field.data_type() {
+                if builder.is::<DecimalBuilder>() {
+                    *builder = Box::new(DecimalBuilder::new(self.batch_size, *precision, *scale));
+                }
+            }
+            // and low-cardinality text columns are dictionary-encoded
+            if let ArrowDataType::Dictionary(_, _) = field.data_type() {
+                if builder.is::<TextBuilder>() {
+                    *builder = Box::new(TextBuilder::dictionary(self.batch_size));
+                }
+            }
+        }
         self.builders.replace(builders);
     }
 
@@ -264,6 +342,9 @@ impl ArrowPartitionWriter {
             .map(|(builder, &dt)| Realize::<FFinishBuilder>::realize(dt)?(builder))
             .collect::<std::result::Result<Vec<_>, crate::connectorx::errors::ConnectorXError>>()?;
         let rb = RecordBatch::try_new(Arc::clone(&self.arrow_schema), columns)?;
+        if let Some(budget) = &self.budget {
+            budget.add_bytes(rb.get_array_memory_size());
+        }
         {
             let mut guard = self
                 .data
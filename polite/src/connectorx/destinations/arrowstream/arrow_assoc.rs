@@ -5,9 +5,9 @@ use crate::connectorx::constants::{
 use crate::connectorx::utils::decimal_to_i128;
 use arrow::array::{
     ArrayBuilder, BooleanBuilder, Date32Builder, Date64Builder, Decimal128Builder, Float32Builder,
-    Float64Builder, Int32Builder, Int64Builder, LargeBinaryBuilder, LargeListBuilder,
-    StringBuilder, Time64NanosecondBuilder, TimestampNanosecondBuilder, UInt32Builder,
-    UInt64Builder,
+    Float64Builder, Int16Builder, Int32Builder, Int64Builder, Int8Builder, LargeBinaryBuilder,
+    LargeListBuilder, StringBuilder, Time64NanosecondBuilder, TimestampNanosecondBuilder,
+    UInt16Builder, UInt32Builder, UInt64Builder, UInt8Builder,
 };
 use arrow::datatypes::Field;
 use arrow::datatypes::{DataType as ArrowDataType, TimeUnit};
@@ -61,8 +61,12 @@ macro_rules! impl_arrow_assoc {
     };
 }
 
+impl_arrow_assoc!(u8, ArrowDataType::UInt8, UInt8Builder);
+impl_arrow_assoc!(u16, ArrowDataType::UInt16, UInt16Builder);
 impl_arrow_assoc!(u32, ArrowDataType::UInt32, UInt32Builder);
 impl_arrow_assoc!(u64, ArrowDataType::UInt64, UInt64Builder);
+impl_arrow_assoc!(i8, ArrowDataType::Int8, Int8Builder);
+impl_arrow_assoc!(i16, ArrowDataType::Int16, Int16Builder);
 impl_arrow_assoc!(i32, ArrowDataType::Int32, Int32Builder);
 impl_arrow_assoc!(i64, ArrowDataType::Int64, Int64Builder);
 impl_arrow_assoc!(f32, ArrowDataType::Float32, Float32Builder);
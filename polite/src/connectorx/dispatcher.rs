@@ -1,6 +1,7 @@
 ///! This module provides [`dispatcher::Dispatcher`], the core struct in ConnectorX
 ///! that drives the data loading from a source to a destination.
 use crate::connectorx::{
+    constants::RECORD_BATCH_SIZE,
     data_order::{coordinate, DataOrder},
     destinations::{Destination, DestinationPartition},
     errors::{ConnectorXError, Result as CXResult},
@@ -8,10 +9,12 @@ use crate::connectorx::{
     sql::CXQuery,
     typesystem::Transport,
 };
+use crate::progress::{Phase, ProgressObserver, PROGRESS_CHUNK};
 use itertools::Itertools;
 use log::debug;
 use rayon::prelude::*;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 /// A dispatcher takes a `S: Source`, a `D: Destination`, a `TP: Transport` and a vector of `queries` as input to
 /// load data from `S` to `D` using the queries.
@@ -20,6 +23,8 @@ pub struct Dispatcher<'a, S, D, TP> {
     dst: &'a mut D,
     queries: Vec<CXQuery<String>>,
     origin_query: Option<String>,
+    progress: Option<Arc<dyn ProgressObserver>>,
+    single_pass: bool,
     _phantom: PhantomData<TP>,
 }
 
@@ -27,7 +32,7 @@ impl<'w, S, D, TP> Dispatcher<'w, S, D, TP>
 where
     S: Source,
     D: Destination,
-    TP: Transport<TSS = S::TypeSystem, TSD = D::TypeSystem, S = S, D = D>,
+    TP: Transport<TSS = S::TypeSystem, TSD = D::TypeSystem, S = S, D = D> + 'w,
 {
     /// Create a new dispatcher by providing a source, a destination and the queries.
     pub fn new<Q>(src: S, dst: &'w mut D, queries: &[Q], origin_query: Option<String>) -> Self
@@ -39,14 +44,34 @@ where
             dst,
             queries: queries.iter().map(Into::into).collect(),
             origin_query,
+            progress: None,
+            single_pass: false,
             _phantom: PhantomData,
         }
     }
 
+    /// Report the phases of the load and the rows fetched by each partition to `progress`.
+    pub fn set_progress(&mut self, progress: Option<Arc<dyn ProgressObserver>>) {
+        self.progress = progress;
+    }
+
+    /// Skip the `LIMIT 1` probe and row counts, taking the schema from the queries' first rows.
+    pub fn set_single_pass(&mut self, single_pass: bool) {
+        self.single_pass = single_pass;
+    }
+
+    fn report_phase(&self, phase: Phase) {
+        if let Some(progress) = &self.progress {
+            progress.phase(phase);
+        }
+    }
+
     pub fn set_pre_execution_queries(&mut self, pre_execution_queries: Option<&[String]>) {
         self.src.set_pre_execution_queries(pre_execution_queries);
     }
 
+    /// Also returns the row count of each partition, where it is known.
+    #[allow(clippy::type_complexity)]
     pub fn prepare(
         mut self,
     ) -> Result<
@@ -56,6 +81,7 @@ where
             Vec<D::Partition<'w>>,
             Vec<S::TypeSystem>,
             Vec<D::TypeSystem>,
+            Vec<Option<usize>>,
         ),
         TP::Error,
     > {
@@ -63,9 +89,10 @@ where
         let dorder = coordinate(S::DATA_ORDERS, D::DATA_ORDERS)?;
         self.src.set_data_order(dorder)?;
         self.src.set_queries(self.queries.as_slice());
-        self.src.set_origin_query(self.origin_query);
+        self.src.set_origin_query(self.origin_query.take());
 
         debug!("Fetching metadata");
+        self.report_phase(Phase::Metadata);
         self.src.fetch_metadata()?;
         let src_schema = self.src.schema();
         let dst_schema = src_schema
@@ -74,6 +101,11 @@ where
             .collect::<CXResult<Vec<_>>>()?;
         let names = self.src.names();
 
+        // Progress observers that ask for it are also given each partition's row count
+        let count_for_progress = self.progress.as_ref().is_some_and(|p| p.wants_totals());
+        if self.dst.needs_count() || count_for_progress {
+            self.report_phase(Phase::Counting);
+        }
         let mut total_rows = if self.dst.needs_count() {
             // return None if cannot derive total count
             debug!("Try get row rounts for entire result");
@@ -83,6 +115,10 @@ where
             Some(0)
         };
         let mut src_partitions: Vec<S::Partition> = self.src.partition()?;
+        let mut part_totals = match (src_partitions.len(), total_rows) {
+            (1, Some(rows)) if self.dst.needs_count() => vec![Some(rows)],
+            (n, _) => vec![None; n],
+        };
         if self.dst.needs_count() && total_rows.is_none() {
             debug!("Manually count rows of each partitioned query and sum up");
             // run queries
@@ -96,6 +132,15 @@ where
                 .map(|partition| partition.nrows())
                 .collect();
             total_rows = Some(part_rows.iter().sum());
+            part_totals = part_rows.into_iter().map(Some).collect();
+        } else if count_for_progress && !self.dst.needs_count() {
+            part_totals = src_partitions
+                .par_iter_mut()
+                .map(|partition| {
+                    partition.result_rows()?;
+                    Ok(Some(partition.nrows()))
+                })
+                .collect::<Result<_, S::Error>>()?;
         }
         let total_rows = total_rows.ok_or_else(ConnectorXError::CountError)?;
 
@@ -115,13 +160,20 @@ where
             dst_partitions,
             src_schema,
             dst_schema,
+            part_totals,
         ))
     }
 
     /// Start the data loading process.
     pub fn run(self) -> Result<(), TP::Error> {
+        if self.single_pass {
+            return self.run_single_pass();
+        }
+
         debug!("Run dispatcher");
-        let (dorder, src_partitions, dst_partitions, src_schema, dst_schema) = self.prepare()?;
+        let progress = self.progress.clone();
+        let (dorder, src_partitions, dst_partitions, src_schema, dst_schema, part_totals) =
+            self.prepare()?;
 
         // #[cfg(feature = "branch")]
         let schemas: Vec<_> = src_schema
@@ -131,6 +183,9 @@ where
             .collect();
 
         debug!("Start writing");
+        if let Some(progress) = &progress {
+            progress.phase(Phase::Fetching);
+        }
         // parse and write
         dst_partitions
             .into_par_iter()
@@ -138,48 +193,66 @@ where
             .enumerate()
             .try_for_each(|(i, (mut dst, mut src))| -> Result<(), TP::Error> {
                 let mut parser = src.parser()?;
+                Self::write_partition(
+                    dorder,
+                    i,
+                    &mut parser,
+                    &mut dst,
+                    &schemas,
+                    progress.as_ref(),
+                    part_totals[i],
+                )
+            })?;
 
-                match dorder {
-                    DataOrder::RowMajor => loop {
-                        let (n, is_last) = parser.fetch_next()?;
-                        dst.aquire_row(n)?;
-                        for _ in 0..n {
-                            #[allow(clippy::needless_range_loop)]
-                            for col in 0..dst.ncols() {
-                                // #[cfg(feature = "branch")]
-                                {
-                                    let (s1, s2) = schemas[col];
-                                    TP::process(s1, s2, &mut parser, &mut dst)?;
-                                }
-                            }
-                        }
-                        if is_last {
-                            break;
-                        }
-                    },
-                    DataOrder::ColumnMajor => loop {
-                        let (n, is_last) = parser.fetch_next()?;
-                        dst.aquire_row(n)?;
-                        #[allow(clippy::needless_range_loop)]
-                        for col in 0..dst.ncols() {
-                            for _ in 0..n {
-                                // #[cfg(feature = "branch")]
-                                {
-                                    let (s1, s2) = schemas[col];
-                                    TP::process(s1, s2, &mut parser, &mut dst)?;
-                                }
-                            }
-                        }
-                        if is_last {
-                            break;
-                        }
-                    },
-                }
+        debug!("Writing finished");
+
+        Ok(())
+    }
+
+    /// Load the data running only the queries themselves: no row counts are taken, and the
+    /// schema comes from the first rows each partition fetches rather than a `LIMIT 1` probe.
+    fn run_single_pass(mut self) -> Result<(), TP::Error> {
+        debug!("Run dispatcher in a single pass");
+        let progress = self.progress.clone();
+        let dorder = coordinate(S::DATA_ORDERS, D::DATA_ORDERS)?;
+        self.src.set_data_order(dorder)?;
+        self.src.set_queries(self.queries.as_slice());
+        self.src.set_origin_query(self.origin_query.take());
+
+        self.report_phase(Phase::Metadata);
+        self.src.fetch_names()?;
+        let names = self.src.names();
+        let mut src_partitions: Vec<S::Partition> = self.src.partition()?;
+        let mut parsers = src_partitions
+            .par_iter_mut()
+            .map(|partition| partition.parser())
+            .collect::<Result<Vec<_>, S::Error>>()?;
+        let peeks = parsers
+            .par_iter_mut()
+            .map(|parser| parser.peek_schema(RECORD_BATCH_SIZE))
+            .collect::<Result<Vec<_>, S::Error>>()?;
+        let src_schema = S::schema_from_peeks(peeks, names.len())?;
+        let dst_schema = src_schema
+            .iter()
+            .map(|&s| TP::convert_typesystem(s))
+            .collect::<CXResult<Vec<_>>>()?;
+        let schemas: Vec<_> = src_schema.into_iter().zip_eq(dst_schema.clone()).collect();
+
+        debug!("Allocate destination memory: 0x{}", names.len());
+        self.dst.allocate(0, &names, &dst_schema, dorder)?;
+        let dst_partitions = self.dst.partition(self.queries.len())?;
 
-                debug!("Finalize partition {}", i);
-                dst.finalize()?;
-                debug!("Partition {} finished", i);
-                Ok(())
+        debug!("Start writing");
+        if let Some(progress) = &progress {
+            progress.phase(Phase::Fetching);
+        }
+        dst_partitions
+            .into_par_iter()
+            .zip_eq(parsers)
+            .enumerate()
+            .try_for_each(|(i, (mut dst, mut parser))| -> Result<(), TP::Error> {
+                let progress = progress.as_ref();
+                Self::write_partition(dorder, i, &mut parser, &mut dst, &schemas, progress, None)
             })?;
 
         debug!("Writing finished");
@@ -187,6 +260,72 @@ where
         Ok(())
     }
 
+    /// Parse every row of partition `i` from `parser` and write it to `dst`.
+    fn write_partition<'s>(
+        dorder: DataOrder,
+        i: usize,
+        parser: &mut <S::Partition as SourcePartition>::Parser<'s>,
+        dst: &mut D::Partition<'w>,
+        schemas: &[(S::TypeSystem, D::TypeSystem)],
+        progress: Option<&Arc<dyn ProgressObserver>>,
+        total: Option<usize>,
+    ) -> Result<(), TP::Error> {
+        let mut fetched = 0;
+        let mut report = |n: usize, is_last: bool| {
+            if let Some(progress) = progress {
+                let before = fetched;
+                fetched += n;
+                if is_last || fetched / PROGRESS_CHUNK > before / PROGRESS_CHUNK {
+                    progress.rows_fetched(i, fetched, total);
+                }
+            }
+        };
+
+        match dorder {
+            DataOrder::RowMajor => loop {
+                let (n, is_last) = parser.fetch_next()?;
+                dst.aquire_row(n)?;
+                for _ in 0..n {
+                    #[allow(clippy::needless_range_loop)]
+                    for col in 0..dst.ncols() {
+                        // #[cfg(feature = "branch")]
+                        {
+                            let (s1, s2) = schemas[col];
+                            TP::process(s1, s2, parser, dst)?;
+                        }
+                    }
+                }
+                report(n, is_last);
+                if is_last {
+                    break;
+                }
+            },
+            DataOrder::ColumnMajor => loop {
+                let (n, is_last) = parser.fetch_next()?;
+                dst.aquire_row(n)?;
+                #[allow(clippy::needless_range_loop)]
+                for col in 0..dst.ncols() {
+                    for _ in 0..n {
+                        // #[cfg(feature = "branch")]
+                        {
+                            let (s1, s2) = schemas[col];
+                            TP::process(s1, s2, parser, dst)?;
+                        }
+                    }
+                }
+                report(n, is_last);
+                if is_last {
+                    break;
+                }
+            },
+        }
+
+        debug!("Finalize partition {}", i);
+        dst.finalize()?;
+        debug!("Partition {} finished", i);
+        Ok(())
+    }
+
     /// Only fetch the metadata (header) of the destination.
     pub fn get_meta(&mut self) -> Result<(), TP::Error> {
         let dorder = coordinate(S::DATA_ORDERS, D::DATA_ORDERS)?;
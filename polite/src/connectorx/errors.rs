@@ -46,6 +46,9 @@ pub enum ConnectorXOutError {
     #[error(transparent)]
     SQLiteArrowTransportError(#[from] crate::connectorx::transports::SQLiteArrowTransportError),
 
+    #[error(transparent)]
+    ThreadPoolError(#[from] rayon::ThreadPoolBuildError),
+
     /// Any other errors that are too trivial to be put here explicitly.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
@@ -1,8 +1,11 @@
+use crate::categorical::dictionary_columns;
 use crate::connectorx::{
     arrow_batch_iter::{ArrowBatchIter, RecordBatchIterator},
     prelude::*,
     sql::CXQuery,
 };
+use crate::limit::ReadBudget;
+use crate::options::ReadOptions;
 use fehler::{throw, throws};
 use log::debug;
 #[allow(unused_imports)]
@@ -15,6 +18,8 @@ pub fn get_arrow(
     origin_query: Option<String>,
     queries: &[CXQuery<String>],
     pre_execution_queries: Option<&[String]>,
+    options: &ReadOptions,
+    budget: Option<Arc<ReadBudget>>,
 ) -> ArrowDestination {
     let mut destination = ArrowDestination::new();
     let protocol = source_conn.proto.as_str();
@@ -25,14 +30,40 @@ pub fn get_arrow(
         SourceType::SQLite => {
             // remove the first "sqlite://" manually since url.path is not correct for windows
             let path = &source_conn.conn.as_str()[9..];
-            let source = SQLiteSource::new(path, queries.len())?;
-            let dispatcher = Dispatcher::<_, _, SQLiteArrowTransport>::new(
+            let mut source = SQLiteSource::with_options(path, queries.len(), options)?;
+            source.set_budget(budget.clone());
+            source.set_pre_execution_queries(pre_execution_queries);
+            let decimals = source.decimal_decls(&queries[0])?;
+            destination.set_decimal_types(
+                decimals
+                    .into_iter()
+                    .map(|d| d.map(|d| (d.precision, d.scale as i8)))
+                    .collect(),
+            );
+            destination.set_dictionary_columns(dictionary_columns(options));
+            // A statement whose probe, or count for progress totals, can't be rewritten is run
+            // once, as written
+            let totals = options.progress.as_ref().is_some_and(|p| p.wants_totals());
+            let mut single_pass = options.single_pass;
+            for query in queries {
+                if single_pass {
+                    break;
+                }
+                single_pass = !source.can_rewrite(query, totals)?;
+            }
+            destination.set_budget(budget);
+            let mut dispatcher = Dispatcher::<_, _, SQLiteArrowTransport>::new(
                 source,
                 &mut destination,
                 queries,
                 origin_query,
             );
-            dispatcher.run()?;
+            dispatcher.set_progress(options.progress.clone());
+            dispatcher.set_single_pass(single_pass);
+            match options.threads.pool()? {
+                Some(pool) => pool.install(|| dispatcher.run())?,
+                None => dispatcher.run()?,
+            }
         }
         _ => throw!(ConnectorXOutError::SourceNotSupport(format!(
             "{:?}",
@@ -50,6 +81,7 @@ pub fn new_record_batch_iter(
     queries: &[CXQuery<String>],
     batch_size: usize,
     pre_execution_queries: Option<&[String]>,
+    pool: Option<Arc<rayon::ThreadPool>>,
 ) -> Box<dyn RecordBatchIterator> {
     let destination = ArrowStreamDestination::new_with_batch_size(batch_size);
     let protocol = source_conn.proto.as_str();
@@ -60,14 +92,16 @@ pub fn new_record_batch_iter(
         SourceType::SQLite => {
             // remove the first "sqlite://" manually since url.path is not correct for windows
             let path = &source_conn.conn.as_str()[9..];
-            let source = SQLiteSource::new(path, queries.len()).unwrap();
-            let batch_iter = ArrowBatchIter::<_, SQLiteArrowStreamTransport>::new(
+            let mut source = SQLiteSource::new(path, queries.len()).unwrap();
+            source.set_pre_execution_queries(pre_execution_queries);
+            let mut batch_iter = ArrowBatchIter::<_, SQLiteArrowStreamTransport>::new(
                 source,
                 destination,
                 origin_query,
                 queries,
             )
             .unwrap();
+            batch_iter.set_thread_pool(pool);
             return Box::new(batch_iter);
         }
         _ => {}
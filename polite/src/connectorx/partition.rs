@@ -2,11 +2,20 @@ use crate::connectorx::errors::{ConnectorXOutError, OutResult};
 use crate::connectorx::source_router::{SourceConn, SourceType};
 // #[cfg(feature = "src_sqlite")]
 use crate::connectorx::sql::get_partition_range_query_sep;
-use crate::connectorx::sql::{single_col_partition_query, CXQuery};
+use crate::connectorx::sql::{
+    plain_table_scan, range_partition_query, rowid_range_query, sample_column_query,
+    single_col_partition_query, single_table, CXQuery,
+};
+pub use crate::options::PartitionStrategy;
 use anyhow::anyhow;
 use fehler::{throw, throws};
+use log::debug;
 // #[cfg(feature = "src_sqlite")]
-use rusqlite::{types::Type, Connection};
+use rusqlite::{
+    types::{Type, Value as SqlValue},
+    Connection, OptionalExtension,
+};
+use sqlparser::ast::Value;
 // #[cfg(feature = "src_sqlite")]
 use sqlparser::dialect::SQLiteDialect;
 use url::Url;
@@ -17,6 +26,7 @@ pub struct PartitionQuery {
     min: Option<i64>,
     max: Option<i64>,
     num: usize,
+    strategy: PartitionStrategy,
 }
 
 impl PartitionQuery {
@@ -27,33 +37,390 @@ impl PartitionQuery {
             min,
             max,
             num,
+            strategy: PartitionStrategy::default(),
         }
     }
+
+    /// Choose how the column's values are split between the partitions.
+    pub fn with_strategy(mut self, strategy: PartitionStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
 }
 
 pub fn partition(part: &PartitionQuery, source_conn: &SourceConn) -> OutResult<Vec<CXQuery>> {
-    let mut queries = vec![];
-    let num = part.num as i64;
-    let (min, max) = match (part.min, part.max) {
-        (None, None) => get_col_range(source_conn, &part.query, &part.column)?,
-        (Some(min), Some(max)) => (min, max),
+    match source_conn.ty {
+        // #[cfg(feature = "src_sqlite")]
+        SourceType::SQLite => {
+            // remove the first "sqlite://" manually since url.path is not correct for windows and for relative path
+            let conn = Connection::open(&source_conn.conn.as_str()[9..])?;
+            sqlite_partition(part, &conn)
+        }
+        _ => unimplemented!("{:?} not implemented!", source_conn.ty),
+    }
+}
+
+/// Split the query into one query per range of the partition column, finding the ranges over
+/// `conn`. The ranges are open at either end, and the first also holds the column's NULLs, so
+/// every row is in exactly one partition; values that repeat may leave fewer partitions than
+/// asked for.
+// #[cfg(feature = "src_sqlite")]
+#[throws(ConnectorXOutError)]
+pub fn sqlite_partition(part: &PartitionQuery, conn: &Connection) -> Vec<CXQuery> {
+    if part.num == 0 {
+        throw!(anyhow!("cannot split a query into 0 partitions"));
+    }
+    let bounds = match part.strategy {
+        PartitionStrategy::EqualWidth => match (part.min, part.max) {
+            (None, None) => equal_width_bounds(conn, &part.query, &part.column, part.num)?,
+            (Some(min), Some(max)) => integer_bounds(min, max, part.num),
+            _ => throw!(anyhow!(
+                "partition_query range can not be partially specified",
+            )),
+        },
+        PartitionStrategy::Quantile { sample_rows } => {
+            match stat4_bounds(conn, &part.query, &part.column, part.num)? {
+                Some(bounds) => bounds,
+                None => sample_bounds(conn, &part.query, &part.column, part.num, sample_rows)?,
+            }
+        }
+    };
+    debug!("Partition bounds on {}: {:?}", part.column, bounds);
+
+    let bounds: Vec<_> = bounds.iter().map(literal).collect::<OutResult<_>>()?;
+    let lowers = std::iter::once(None).chain(bounds.iter().cloned().map(Some));
+    let uppers = bounds
+        .iter()
+        .cloned()
+        .map(Some)
+        .chain(std::iter::once(None));
+    lowers
+        .zip(uppers)
+        .map(|(lower, upper)| {
+            let query =
+                range_partition_query(&part.query, &part.column, lower, upper, &SQLiteDialect {})?;
+            Ok(CXQuery::Wrapped(query))
+        })
+        .collect::<OutResult<_>>()?
+}
+
+/// Split a plain scan of one rowid table into `num` ranges of its rowid (or INTEGER PRIMARY
+/// KEY), or `None` if the query is anything else: a view, a `WITHOUT ROWID` or virtual table,
+/// or more than a scan (see [`plain_table_scan`]).
+// #[cfg(feature = "src_sqlite")]
+pub fn sqlite_rowid_partition(
+    query: &str,
+    num: usize,
+    conn: &Connection,
+) -> OutResult<Option<Vec<CXQuery>>> {
+    let Some(table) = plain_table_scan(query, &SQLiteDialect {}) else {
+        return Ok(None);
+    };
+    let is_rowid_table: bool = conn
+        .query_row(
+            "SELECT type = 'table' AND NOT wr FROM pragma_table_list
+             WHERE schema = 'main' AND name = ?1 COLLATE NOCASE",
+            [&table],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(false);
+    if !is_rowid_table || num < 2 {
+        return Ok(None);
+    }
+
+    // An INTEGER PRIMARY KEY is the rowid; otherwise a rowid alias no column has taken
+    let columns: Vec<(String, String, i64)> = conn
+        .prepare("SELECT name, upper(type), pk FROM pragma_table_info(?1)")?
+        .query_map([&table], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    let rowid = match columns
+        .iter()
+        .filter(|(_, _, pk)| *pk > 0)
+        .collect::<Vec<_>>()[..]
+    {
+        [(name, ty, _)] if ty == "INTEGER" => Some(name.clone()),
+        _ => ["rowid", "_rowid_", "oid"]
+            .into_iter()
+            .find(|alias| {
+                !columns
+                    .iter()
+                    .any(|(name, _, _)| name.eq_ignore_ascii_case(alias))
+            })
+            .map(str::to_string),
+    };
+    let Some(rowid) = rowid else {
+        return Ok(None);
+    };
+
+    // SQLite only optimize min max queries when there is only one aggregation
+    let quoted = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+    let bound = |agg: &str| -> rusqlite::Result<Option<i64>> {
+        let sql = format!("SELECT {agg}({}) FROM {}", quoted(&rowid), quoted(&table));
+        conn.query_row(&sql, [], |row| row.get(0))
+    };
+    let (Some(min), Some(max)) = (bound("min")?, bound("max")?) else {
+        return Ok(None);
+    };
+    let bounds: Vec<i64> = integer_bounds(min, max, num)
+        .into_iter()
+        .filter_map(|v| match v {
+            SqlValue::Integer(v) => Some(v),
+            _ => None,
+        })
+        .collect();
+    debug!("Rowid partition bounds on {}: {:?}", table, bounds);
+
+    let lowers = std::iter::once(None).chain(bounds.iter().copied().map(Some));
+    let uppers = bounds
+        .iter()
+        .copied()
+        .map(Some)
+        .chain(std::iter::once(None));
+    let queries = lowers
+        .zip(uppers)
+        .map(|(lower, upper)| {
+            let query = rowid_range_query(query, &rowid, lower, upper, &SQLiteDialect {})?;
+            Ok(CXQuery::Wrapped(query))
+        })
+        .collect::<OutResult<_>>()?;
+    Ok(Some(queries))
+}
+
+/// A bound as an SQL literal.
+#[throws(ConnectorXOutError)]
+fn literal(value: &SqlValue) -> Value {
+    match value {
+        SqlValue::Integer(v) => Value::Number(v.to_string(), false),
+        SqlValue::Real(v) if v.is_finite() => Value::Number(format!("{v:?}"), false),
+        SqlValue::Text(v) => Value::SingleQuotedString(v.clone()),
+        v => throw!(anyhow!("cannot partition on the value {:?}", v)),
+    }
+}
+
+/// `num - 1` bounds splitting `[min, max]` into ranges of equal width.
+fn integer_bounds(min: i64, max: i64, num: usize) -> Vec<SqlValue> {
+    let (min, max, num) = (min as i128, max as i128, num as i128);
+    let width = (max - min + 1) / num;
+    if width <= 0 {
+        return vec![];
+    }
+    (1..num)
+        .map(|i| SqlValue::Integer((min + i * width) as i64))
+        .collect()
+}
+
+/// `num - 1` bounds splitting the column's range into ranges of equal width: of its values if
+/// numeric, or of its julian days if it holds dates or datetimes as text.
+// #[cfg(feature = "src_sqlite")]
+#[throws(ConnectorXOutError)]
+fn equal_width_bounds(conn: &Connection, query: &str, col: &str, num: usize) -> Vec<SqlValue> {
+    // SQLite only optimize min max queries when there is only one aggregation
+    // https://www.sqlite.org/optoverview.html#minmax
+    let (min_query, max_query) = get_partition_range_query_sep(query, col, &SQLiteDialect {})?;
+    let min: SqlValue = conn.query_row(min_query.as_str(), [], |row| row.get(0))?;
+    let max: SqlValue = conn.query_row(max_query.as_str(), [], |row| row.get(0))?;
+    let steps = |lo: f64, hi: f64| (1..num).map(move |i| lo + (hi - lo) * i as f64 / num as f64);
+
+    let mut bounds = match (&min, &max) {
+        // no non-null values
+        (SqlValue::Null, _) | (_, SqlValue::Null) => vec![],
+        (SqlValue::Integer(min), SqlValue::Integer(max)) => integer_bounds(*min, *max, num),
+        (SqlValue::Integer(_) | SqlValue::Real(_), SqlValue::Integer(_) | SqlValue::Real(_)) => {
+            let as_f64 = |v: &SqlValue| match v {
+                SqlValue::Integer(v) => *v as f64,
+                SqlValue::Real(v) => *v,
+                _ => unreachable!(),
+            };
+            steps(as_f64(&min), as_f64(&max))
+                .map(SqlValue::Real)
+                .collect()
+        }
+        (SqlValue::Text(min), SqlValue::Text(max)) => {
+            let days: (Option<f64>, Option<f64>) =
+                conn.query_row("SELECT julianday(?1), julianday(?2)", [min, max], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?;
+            let (Some(lo), Some(hi)) = days else {
+                throw!(anyhow!(
+                    "equal-width partitioning needs a numeric, date or datetime column, but '{}' \
+                     holds text; partition it at quantiles instead",
+                    col
+                ));
+            };
+            // Bounds are written like the column's values, so that they compare as text
+            let format = if min.len() == 10 {
+                "%Y-%m-%d"
+            } else if min.contains('T') {
+                "%Y-%m-%dT%H:%M:%S"
+            } else {
+                "%Y-%m-%d %H:%M:%S"
+            };
+            steps(lo, hi)
+                .map(|day| {
+                    conn.query_row("SELECT strftime(?1, ?2)", (format, day), |row| {
+                        Ok(SqlValue::Text(row.get(0)?))
+                    })
+                })
+                .collect::<rusqlite::Result<_>>()?
+        }
         _ => throw!(anyhow!(
-            "partition_query range can not be partially specified",
+            "cannot split column '{}' into equal-width ranges between {:?} and {:?}",
+            col,
+            min,
+            max
         )),
     };
+    bounds.dedup();
+    bounds
+}
 
-    let partition_size = (max - min + 1) / num;
+/// Bounds at the column's quantiles from the `sqlite_stat4` samples of an index on it, when
+/// the query reads a single table that `ANALYZE` has sampled.
+// #[cfg(feature = "src_sqlite")]
+fn stat4_bounds(
+    conn: &Connection,
+    query: &str,
+    col: &str,
+    num: usize,
+) -> OutResult<Option<Vec<SqlValue>>> {
+    let Some(table) = single_table(query, &SQLiteDialect {}) else {
+        return Ok(None);
+    };
+    let has_stat4: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_schema WHERE name = 'sqlite_stat4')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_stat4 {
+        return Ok(None);
+    }
+    // An index whose first column is the partition column
+    let index: Option<String> = conn
+        .query_row(
+            "SELECT il.name FROM pragma_index_list(?1) AS il
+             WHERE (SELECT ii.name FROM pragma_index_info(il.name) AS ii WHERE ii.seqno = 0)
+                   = ?2 COLLATE NOCASE
+             LIMIT 1",
+            [&table, col],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(index) = index else {
+        return Ok(None);
+    };
+    let rows: Option<String> = conn
+        .query_row(
+            "SELECT stat FROM sqlite_stat1 WHERE tbl = ?1 AND idx = ?2",
+            [&table, &index],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(rows) = rows.and_then(|stat| stat.split(' ').next()?.parse::<u64>().ok()) else {
+        return Ok(None);
+    };
 
-    for i in 0..num {
-        let lower = min + i * partition_size;
-        let upper = match i == num - 1 {
-            true => max + 1,
-            false => min + (i + 1) * partition_size,
-        };
-        let partition_query = get_part_query(source_conn, &part.query, &part.column, lower, upper)?;
-        queries.push(partition_query);
+    // Each sample is an index key, with the number of rows whose first column is less than it
+    let mut stmt = conn.prepare(
+        "SELECT nlt, sample FROM sqlite_stat4 WHERE tbl = ?1 AND idx = ?2 ORDER BY rowid",
+    )?;
+    let samples: Vec<(u64, SqlValue)> = stmt
+        .query_map([&table, &index], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?
+        .filter_map(|sample| {
+            let (nlt, record) = sample.ok()?;
+            let nlt = nlt.split(' ').next()?.parse().ok()?;
+            match record_first_value(&record)? {
+                SqlValue::Null => None,
+                value => Some((nlt, value)),
+            }
+        })
+        .collect();
+    if samples.is_empty() {
+        return Ok(None);
+    }
+
+    let mut bounds: Vec<SqlValue> = (1..num as u64)
+        .filter_map(|i| {
+            let target = rows * i / num as u64;
+            samples
+                .iter()
+                .min_by_key(|(nlt, _)| nlt.abs_diff(target))
+                .map(|(_, value)| value.clone())
+        })
+        .collect();
+    bounds.dedup();
+    Ok(Some(bounds))
+}
+
+/// Bounds at the quantiles of an evenly spaced sample of up to `sample_rows` of the column's
+/// values.
+// #[cfg(feature = "src_sqlite")]
+#[throws(ConnectorXOutError)]
+fn sample_bounds(
+    conn: &Connection,
+    query: &str,
+    col: &str,
+    num: usize,
+    sample_rows: usize,
+) -> Vec<SqlValue> {
+    let sample_query = sample_column_query(query, col, sample_rows, &SQLiteDialect {})?;
+    let mut stmt = conn.prepare(&sample_query)?;
+    let sample: Vec<SqlValue> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    if sample.is_empty() {
+        return vec![];
+    }
+    let mut bounds: Vec<SqlValue> = (1..num)
+        .map(|i| sample[i * sample.len() / num].clone())
+        .collect();
+    bounds.dedup();
+    bounds
+}
+
+/// The first field of an SQLite record, the format of a `sqlite_stat4` sample.
+/// <https://www.sqlite.org/fileformat.html#record_format>
+fn record_first_value(record: &[u8]) -> Option<SqlValue> {
+    let (header_len, pos) = varint(record, 0)?;
+    let (serial_type, _) = varint(record, pos)?;
+    let body = record.get(header_len as usize..)?;
+    let int = |len: usize| {
+        let bytes = body.get(..len)?;
+        let init = if bytes.first()? & 0x80 != 0 { -1 } else { 0 };
+        Some(bytes.iter().fold(init, |v: i64, &b| (v << 8) | b as i64))
+    };
+    Some(match serial_type {
+        0 => SqlValue::Null,
+        1..=4 => SqlValue::Integer(int(serial_type as usize)?),
+        5 => SqlValue::Integer(int(6)?),
+        6 => SqlValue::Integer(int(8)?),
+        7 => SqlValue::Real(f64::from_be_bytes(body.get(..8)?.try_into().ok()?)),
+        8 => SqlValue::Integer(0),
+        9 => SqlValue::Integer(1),
+        n if n >= 12 && n % 2 == 0 => SqlValue::Blob(body.get(..(n as usize - 12) / 2)?.to_vec()),
+        n if n >= 13 => {
+            SqlValue::Text(String::from_utf8_lossy(body.get(..(n as usize - 13) / 2)?).into_owned())
+        }
+        _ => return None,
+    })
+}
+
+/// An SQLite varint at `pos`, and the position after it.
+fn varint(buf: &[u8], pos: usize) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for i in 0..9 {
+        let byte = *buf.get(pos + i)?;
+        if i == 8 {
+            return Some(((value << 8) | byte as u64, pos + 9));
+        }
+        value = (value << 7) | (byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            return Some((value, pos + i + 1));
+        }
     }
-    Ok(queries)
+    None
 }
 
 pub fn get_col_range(source_conn: &SourceConn, query: &str, col: &str) -> OutResult<(i64, i64)> {
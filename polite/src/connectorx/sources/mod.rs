@@ -29,6 +29,18 @@ pub trait Source {
     }
 
     fn fetch_metadata(&mut self) -> Result<(), Self::Error>;
+
+    /// Fetch the column names of the result without running the queries, for a single-pass
+    /// read whose types are found by [`PartitionParser::peek_schema`] instead.
+    fn fetch_names(&mut self) -> Result<(), Self::Error>;
+
+    /// The schema of a single-pass read, from the types each partition's first rows showed
+    /// (`None` for a partition that returned no rows).
+    fn schema_from_peeks(
+        peeks: Vec<Option<Vec<Option<Self::TypeSystem>>>>,
+        ncols: usize,
+    ) -> Result<Vec<Self::TypeSystem>, Self::Error>;
+
     /// Get total number of rows if available
     fn result_rows(&mut self) -> Result<Option<usize>, Self::Error>;
 
@@ -79,6 +91,14 @@ pub trait PartitionParser<'a>: Send {
     /// There might be rows that are not consumed yet when calling the next fetch_next.
     /// The function might be called even after the last batch is fetched.
     fn fetch_next(&mut self) -> Result<(usize, bool), Self::Error>;
+
+    /// Fetch up to `max_rows` rows ahead, keeping them to be returned by `fetch_next`, until the
+    /// type of every column can be told from its values. Returns those types, or `None` if the
+    /// partition has no rows.
+    fn peek_schema(
+        &mut self,
+        max_rows: usize,
+    ) -> Result<Option<Vec<Option<Self::TypeSystem>>>, Self::Error>;
 }
 
 /// A type implemented `Produce<T>` means that it can produce a value `T` by consuming part of it's raw data buffer.
@@ -6,6 +6,19 @@ pub enum SQLiteSourceError {
     #[error("Cannot infer type from null for SQLite")]
     InferTypeFromNull,
 
+    /// A stored value that cannot be read as its column's type. The database path and query
+    /// are left empty here and filled in by the caller.
+    #[error(transparent)]
+    Conversion(Box<crate::error::ConversionError>),
+
+    /// A pre-execution statement that failed on one of the pool's connections.
+    #[error("Pre-execution statement `{sql}` failed: {source}")]
+    PreExecution {
+        sql: String,
+        #[source]
+        source: Box<rusqlite::Error>,
+    },
+
     #[error(transparent)]
     ConnectorXError(#[from] crate::connectorx::errors::ConnectorXError),
 
@@ -4,52 +4,157 @@ mod errors;
 mod typesystem;
 
 pub use self::errors::SQLiteSourceError;
+use crate::cancel::{CancellationToken, Registration};
 use crate::connectorx::{
     data_order::DataOrder,
     errors::ConnectorXError,
     sources::{PartitionParser, Produce, Source, SourcePartition},
-    sql::{count_query, limit1_query, CXQuery},
+    sql::{count_query, is_rewritable, limit1_query, CXQuery},
     utils::DummyBox,
 };
+use crate::db::{configure, open_flags};
+use crate::error::ConversionError;
+use crate::limit::ReadBudget;
+use crate::options::{ConnectOptions, ReadOptions};
 use anyhow::anyhow;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use fallible_streaming_iterator::FallibleStreamingIterator;
 use fehler::{throw, throws};
 use log::debug;
 use owning_ref::OwningHandle;
-use r2d2::{Pool, PooledConnection};
+use r2d2::{CustomizeConnection, Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::{Row, Rows, Statement};
+use rusqlite::types::{FromSql, FromSqlError, Type, Value, ValueRef};
+use rusqlite::{Connection, ErrorCode, Rows, Statement};
+use rust_decimal::Decimal;
 use sqlparser::dialect::SQLiteDialect;
+use std::collections::VecDeque;
 use std::convert::TryFrom;
-pub use typesystem::SQLiteTypeSystem;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+pub use typesystem::{DecimalDecl, SQLiteTypeSystem};
 use urlencoding::decode;
 
 pub struct SQLiteSource {
     pool: Pool<SqliteConnectionManager>,
+    pre_execution_queries: Arc<RwLock<Vec<String>>>,
+    pre_execution_failure: Arc<Mutex<Option<(String, rusqlite::Error)>>>,
     origin_query: Option<String>,
     queries: Vec<CXQuery<String>>,
     names: Vec<String>,
     schema: Vec<SQLiteTypeSystem>,
+    options: ReadOptions,
+    budget: Option<Arc<ReadBudget>>,
 }
 
 impl SQLiteSource {
     #[throws(SQLiteSourceError)]
     pub fn new(conn: &str, nconn: usize) -> Self {
+        Self::with_options(conn, nconn, &ReadOptions::default())?
+    }
+
+    /// A source whose pooled connections are interrupted when `options.cancel` is cancelled.
+    #[throws(SQLiteSourceError)]
+    pub fn with_options(conn: &str, nconn: usize, options: &ReadOptions) -> Self {
         let decoded_conn = decode(conn)?.into_owned();
         debug!("decoded conn: {}", decoded_conn);
-        let manager = SqliteConnectionManager::file(decoded_conn);
+        let manager =
+            SqliteConnectionManager::file(decoded_conn).with_flags(open_flags(&options.connect));
+        let pre_execution_queries = Arc::new(RwLock::new(vec![]));
+        let pre_execution_failure = Arc::new(Mutex::new(None));
+        // Connections are opened on first use, after the pre-execution queries are known
         let pool = r2d2::Pool::builder()
             .max_size(nconn as u32)
+            .min_idle(Some(0))
+            .connection_customizer(Box::new(SetupConnection {
+                connect: options.connect.clone(),
+                pre_execution_queries: Arc::clone(&pre_execution_queries),
+                pre_execution_failure: Arc::clone(&pre_execution_failure),
+                cancel: options.cancel.clone(),
+                registrations: Mutex::new(vec![]),
+            }))
             .build(manager)?;
 
         Self {
             pool,
+            pre_execution_queries,
+            pre_execution_failure,
             origin_query: None,
             queries: vec![],
             names: vec![],
             schema: vec![],
+            options: options.clone(),
+            budget: None,
+        }
+    }
+
+    /// A connection from the pool, failing if a pre-execution statement failed on it.
+    #[throws(SQLiteSourceError)]
+    fn connection(&self) -> PooledConnection<SqliteConnectionManager> {
+        let conn = self.pool.get()?;
+        if let Some((sql, source)) = self.pre_execution_failure.lock().unwrap().take() {
+            throw!(SQLiteSourceError::PreExecution {
+                sql,
+                source: Box::new(source),
+            });
+        }
+        conn
+    }
+
+    /// Stop every partition once the read has used up `budget`.
+    pub fn set_budget(&mut self, budget: Option<Arc<ReadBudget>>) {
+        self.budget = budget;
+    }
+
+    /// Declared decimal precision and scale of each column the query returns.
+    #[throws(SQLiteSourceError)]
+    pub fn decimal_decls(&self, query: &CXQuery<String>) -> Vec<Option<DecimalDecl>> {
+        let conn = self.connection()?;
+        let stmt = conn.prepare(query.as_str())?;
+        stmt.columns()
+            .iter()
+            .map(|col| col.decl_type().and_then(DecimalDecl::parse))
+            .collect()
+    }
+
+    /// Whether the default read can run `query` through its rewrites: the `LIMIT 1` probe for
+    /// the schema and, with `count`, the `COUNT(*)` for progress totals. A statement sqlparser
+    /// can't rewrite, or whose rewrite SQLite won't prepare, has to be read once as written.
+    #[throws(SQLiteSourceError)]
+    pub fn can_rewrite(&self, query: &CXQuery<String>, count: bool) -> bool {
+        if !is_rewritable(query, &SQLiteDialect {}) {
+            return false;
+        }
+        let mut rewrites = vec![limit1_query(query, &SQLiteDialect {})];
+        if count {
+            rewrites.push(count_query(query, &SQLiteDialect {}));
         }
+        let conn = self.connection()?;
+        rewrites.iter().all(|rewrite| {
+            let prepared = match rewrite {
+                Ok(rewrite) => conn
+                    .prepare(rewrite.as_str())
+                    .map(drop)
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(e) = &prepared {
+                debug!("can't rewrite {query}: {e}, running it verbatim");
+            }
+            prepared.is_ok()
+        })
+    }
+
+    fn finish_type(&self, ty: SQLiteTypeSystem) -> SQLiteTypeSystem {
+        finish_type(ty, self.options.widen_integers)
+    }
+}
+
+fn finish_type(ty: SQLiteTypeSystem, widen_integers: bool) -> SQLiteTypeSystem {
+    if widen_integers {
+        ty.widened()
+    } else {
+        ty
     }
 }
 
@@ -73,6 +178,11 @@ where
         self.queries = queries.iter().map(|q| q.map(Q::to_string)).collect();
     }
 
+    fn set_pre_execution_queries(&mut self, pre_execution_queries: Option<&[String]>) {
+        *self.pre_execution_queries.write().unwrap() =
+            pre_execution_queries.unwrap_or_default().to_vec();
+    }
+
     fn set_origin_query(&mut self, query: Option<String>) {
         self.origin_query = query;
     }
@@ -80,7 +190,7 @@ where
     #[throws(SQLiteSourceError)]
     fn fetch_metadata(&mut self) {
         assert!(!self.queries.is_empty());
-        let conn = self.pool.get()?;
+        let conn = self.connection()?;
         let mut names = vec![];
         let mut types = vec![];
         let mut num_empty = 0;
@@ -118,7 +228,10 @@ where
                 Ok(()) => {
                     if !types.contains(&None) {
                         self.names = names;
-                        self.schema = types.into_iter().map(|t| t.unwrap()).collect();
+                        self.schema = types
+                            .into_iter()
+                            .map(|t| self.finish_type(t.unwrap()))
+                            .collect();
                         return;
                     } else if i == self.queries.len() - 1 {
                         debug!(
@@ -153,12 +266,44 @@ where
         self.schema = vec![SQLiteTypeSystem::Text(false); self.names.len()];
     }
 
+    #[throws(SQLiteSourceError)]
+    fn fetch_names(&mut self) {
+        assert!(!self.queries.is_empty());
+        let conn = self.connection()?;
+        let stmt = conn.prepare(self.queries[0].as_str())?;
+        self.names = stmt
+            .column_names()
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+    }
+
+    #[throws(SQLiteSourceError)]
+    fn schema_from_peeks(
+        peeks: Vec<Option<Vec<Option<SQLiteTypeSystem>>>>,
+        ncols: usize,
+    ) -> Vec<SQLiteTypeSystem> {
+        let peeks: Vec<_> = peeks.into_iter().flatten().collect();
+        if peeks.is_empty() {
+            // every partition is empty: read all columns as string, as fetch_metadata does
+            return vec![SQLiteTypeSystem::Text(false); ncols];
+        }
+        (0..ncols)
+            .map(|col| {
+                peeks
+                    .iter()
+                    .find_map(|types| types[col])
+                    .ok_or(SQLiteSourceError::InferTypeFromNull)
+            })
+            .collect::<Result<_, _>>()?
+    }
+
     #[throws(SQLiteSourceError)]
     fn result_rows(&mut self) -> Option<usize> {
         match &self.origin_query {
             Some(q) => {
                 let cxq = CXQuery::Naked(q.clone());
-                let conn = self.pool.get()?;
+                let conn = self.connection()?;
                 let nrows =
                     conn.query_row(count_query(&cxq, &SQLiteDialect {})?.as_str(), [], |row| {
                         Ok(row.get::<_, i64>(0)? as usize)
@@ -180,21 +325,60 @@ where
     #[throws(SQLiteSourceError)]
     fn partition(self) -> Vec<Self::Partition> {
         let mut ret = vec![];
-        for query in self.queries {
-            let conn = self.pool.get()?;
-
-            ret.push(SQLiteSourcePartition::new(conn, &query, &self.schema));
+        for query in &self.queries {
+            let conn = self.connection()?;
+
+            ret.push(
+                SQLiteSourcePartition::new(conn, query, &self.schema)
+                    .with_cancel(self.options.cancel.clone())
+                    .with_budget(self.budget.clone())
+                    .with_widen_integers(self.options.widen_integers),
+            );
         }
         ret
     }
 }
 
+/// Prepares every connection the pool opens: applies the read's connection PRAGMAs, runs its
+/// pre-execution statements and registers it with its cancellation token.
+#[derive(Debug)]
+struct SetupConnection {
+    connect: ConnectOptions,
+    pre_execution_queries: Arc<RwLock<Vec<String>>>,
+    pre_execution_failure: Arc<Mutex<Option<(String, rusqlite::Error)>>>,
+    cancel: Option<CancellationToken>,
+    registrations: Mutex<Vec<Registration>>,
+}
+
+impl CustomizeConnection<Connection, rusqlite::Error> for SetupConnection {
+    fn on_acquire(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
+        configure(conn, &self.connect)?;
+        for sql in self.pre_execution_queries.read().unwrap().iter() {
+            // An error returned here would only be logged while the pool retried until its
+            // timeout, so it is kept for `SQLiteSource::connection` to report instead
+            if let Err(e) = conn.execute_batch(sql) {
+                let mut failure = self.pre_execution_failure.lock().unwrap();
+                failure.get_or_insert((sql.clone(), e));
+                break;
+            }
+        }
+        if let Some(token) = &self.cancel {
+            let registration = token.register(conn);
+            self.registrations.lock().unwrap().push(registration);
+        }
+        Ok(())
+    }
+}
+
 pub struct SQLiteSourcePartition {
     conn: PooledConnection<SqliteConnectionManager>,
     query: CXQuery<String>,
     schema: Vec<SQLiteTypeSystem>,
     nrows: usize,
     ncols: usize,
+    cancel: Option<CancellationToken>,
+    budget: Option<Arc<ReadBudget>>,
+    widen_integers: bool,
 }
 
 impl SQLiteSourcePartition {
@@ -209,8 +393,29 @@ impl SQLiteSourcePartition {
             schema: schema.to_vec(),
             nrows: 0,
             ncols: schema.len(),
+            cancel: None,
+            budget: None,
+            widen_integers: false,
         }
     }
+
+    /// Widen the integer types [`PartitionParser::peek_schema`] finds to `Int64`.
+    pub fn with_widen_integers(mut self, widen_integers: bool) -> Self {
+        self.widen_integers = widen_integers;
+        self
+    }
+
+    /// Stop fetching rows once `budget` is used up.
+    pub fn with_budget(mut self, budget: Option<Arc<ReadBudget>>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Stop fetching rows once `cancel` is cancelled.
+    pub fn with_cancel(mut self, cancel: Option<CancellationToken>) -> Self {
+        self.cancel = cancel;
+        self
+    }
 }
 
 impl SourcePartition for SQLiteSourcePartition {
@@ -229,7 +434,12 @@ impl SourcePartition for SQLiteSourcePartition {
 
     #[throws(SQLiteSourceError)]
     fn parser(&mut self) -> Self::Parser<'_> {
-        SQLiteSourcePartitionParser::new(&self.conn, self.query.as_str(), &self.schema)?
+        let mut parser =
+            SQLiteSourcePartitionParser::new(&self.conn, self.query.as_str(), &self.schema)?;
+        parser.cancel = self.cancel.clone();
+        parser.budget = self.budget.clone();
+        parser.widen_integers = self.widen_integers;
+        parser
     }
 
     fn nrows(&self) -> usize {
@@ -246,9 +456,18 @@ unsafe impl<'a> Send for SQLiteSourcePartitionParser<'a> {}
 pub struct SQLiteSourcePartitionParser<'a> {
     rows: OwningHandle<Box<Statement<'a>>, DummyBox<Rows<'a>>>,
     ncols: usize,
+    decimals: Vec<Option<DecimalDecl>>,
     current_col: usize,
+    rows_fetched: usize,
     current_consumed: bool,
     is_finished: bool,
+    cancel: Option<CancellationToken>,
+    budget: Option<Arc<ReadBudget>>,
+    widen_integers: bool,
+    /// Rows read ahead by `peek_schema`, returned before the rest of the cursor's rows.
+    peeked: VecDeque<Vec<Value>>,
+    /// The current row, when it was one of the peeked rows.
+    current_peeked: Option<Vec<Value>>,
 }
 
 impl<'a> SQLiteSourcePartitionParser<'a> {
@@ -256,9 +475,15 @@ impl<'a> SQLiteSourcePartitionParser<'a> {
     pub fn new(
         conn: &'a PooledConnection<SqliteConnectionManager>,
         query: &str,
-        schema: &[SQLiteTypeSystem],
+        _schema: &[SQLiteTypeSystem],
     ) -> Self {
         let stmt: Statement<'a> = conn.prepare(query)?;
+        let ncols = stmt.column_count();
+        let decimals = stmt
+            .columns()
+            .iter()
+            .map(|col| col.decl_type().and_then(DecimalDecl::parse))
+            .collect();
 
         // Safety: DummyBox borrows the on-heap stmt, which is owned by the OwningHandle.
         // No matter how we move the owning handle (thus the Box<Statment>), the Statement
@@ -269,22 +494,62 @@ impl<'a> SQLiteSourcePartitionParser<'a> {
             });
         Self {
             rows,
-            ncols: schema.len(),
+            ncols,
+            decimals,
             current_col: 0,
+            rows_fetched: 0,
             current_consumed: true,
             is_finished: false,
+            cancel: None,
+            budget: None,
+            widen_integers: false,
+            peeked: VecDeque::new(),
+            current_peeked: None,
         }
     }
 
+    /// The statement, the next value to produce and its column.
     #[throws(SQLiteSourceError)]
-    fn next_loc(&mut self) -> (&Row, usize) {
+    fn next_value(&mut self) -> (&Statement<'a>, ValueRef<'_>, usize) {
         self.current_consumed = true;
-        let row: &Row = (*self.rows)
-            .get()
-            .ok_or_else(|| anyhow!("Sqlite empty current row"))?;
         let col = self.current_col;
         self.current_col = (self.current_col + 1) % self.ncols;
-        (row, col)
+        let value = match &self.current_peeked {
+            Some(row) => ValueRef::from(&row[col]),
+            None => (*self.rows)
+                .get()
+                .ok_or_else(|| anyhow!("Sqlite empty current row"))?
+                .get_ref(col)?,
+        };
+        (&**self.rows.as_owner(), value, col)
+    }
+
+    /// Step the cursor to its next row, returning whether there is one.
+    #[throws(SQLiteSourceError)]
+    fn step(&mut self) -> bool {
+        if self.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+            self.is_finished = true;
+            return false;
+        }
+
+        match (*self.rows).next() {
+            // Over the read's row or byte limit: end the partition without this row
+            Ok(Some(_)) if self.budget.as_ref().is_some_and(|b| !b.take_row()) => {
+                self.is_finished = true;
+                false
+            }
+            Ok(Some(_)) => true,
+            Ok(None) => {
+                self.is_finished = true;
+                false
+            }
+            // Cancelled mid-step: end the partition, keeping the rows already fetched
+            Err(e) if e.sqlite_error_code() == Some(ErrorCode::OperationInterrupted) => {
+                self.is_finished = true;
+                false
+            }
+            Err(e) => throw!(e),
+        }
     }
 }
 
@@ -298,20 +563,57 @@ impl<'a> PartitionParser<'a> for SQLiteSourcePartitionParser<'a> {
 
         if !self.current_consumed {
             return (1, false);
-        } else if self.is_finished {
-            return (0, true);
         }
 
-        match (*self.rows).next()? {
-            Some(_) => {
-                self.current_consumed = false;
-                (1, false)
+        self.current_peeked = self.peeked.pop_front();
+        if self.current_peeked.is_some() || (!self.is_finished && self.step()?) {
+            self.rows_fetched += 1;
+            self.current_consumed = false;
+            (1, false)
+        } else {
+            (0, true)
+        }
+    }
+
+    #[throws(SQLiteSourceError)]
+    fn peek_schema(&mut self, max_rows: usize) -> Option<Vec<Option<SQLiteTypeSystem>>> {
+        let decls: Vec<Option<String>> = self
+            .rows
+            .as_owner()
+            .columns()
+            .iter()
+            .map(|col| col.decl_type().map(str::to_string))
+            .collect();
+        let mut types = vec![None; self.ncols];
+
+        while self.peeked.len() < max_rows
+            && (self.peeked.is_empty() || types.contains(&None))
+            && !self.is_finished
+            && self.step()?
+        {
+            let row = (*self.rows)
+                .get()
+                .ok_or_else(|| anyhow!("Sqlite empty current row"))?;
+            let mut values = Vec::with_capacity(self.ncols);
+            for (col, ty) in types.iter_mut().enumerate() {
+                let value = row.get_ref(col)?;
+                if ty.is_none() {
+                    *ty = SQLiteTypeSystem::try_from((decls[col].as_deref(), value.data_type()))
+                        .ok()
+                        .map(|t| finish_type(t, self.widen_integers));
+                }
+                values.push(Value::from(value));
             }
-            None => {
-                self.is_finished = true;
-                (0, true)
+            self.peeked.push_back(values);
+        }
+
+        if self.is_finished {
+            // The whole partition was peeked: a column still untyped is null in every row
+            for ty in types.iter_mut().filter(|ty| ty.is_none()) {
+                *ty = Some(SQLiteTypeSystem::Text(true));
             }
         }
+        (!self.peeked.is_empty()).then_some(types)
     }
 }
 
@@ -323,9 +625,9 @@ macro_rules! impl_produce {
 
                 #[throws(SQLiteSourceError)]
                 fn produce(&'r mut self) -> $t {
-                    let (row, col) = self.next_loc()?;
-                    let val = row.get(col)?;
-                    val
+                    let row_idx = self.rows_fetched.saturating_sub(1);
+                    let (stmt, value, col) = self.next_value()?;
+                    get_value(stmt, value, col, row_idx, stringify!($t))?
                 }
             }
 
@@ -334,9 +636,9 @@ macro_rules! impl_produce {
 
                 #[throws(SQLiteSourceError)]
                 fn produce(&'r mut self) -> Option<$t> {
-                    let (row, col) = self.next_loc()?;
-                    let val = row.get(col)?;
-                    val
+                    let row_idx = self.rows_fetched.saturating_sub(1);
+                    let (stmt, value, col) = self.next_value()?;
+                    get_value(stmt, value, col, row_idx, stringify!($t))?
                 }
             }
         )+
@@ -346,8 +648,6 @@ macro_rules! impl_produce {
 impl_produce!(
     bool,
     i64,
-    i32,
-    i16,
     f64,
     Box<str>,
     NaiveDate,
@@ -355,3 +655,221 @@ impl_produce!(
     NaiveDateTime,
     Vec<u8>,
 );
+
+/// Read a value, reporting the column, row and stored value if it can't be converted.
+fn get_value<T: FromSql>(
+    stmt: &Statement<'_>,
+    value: ValueRef<'_>,
+    col: usize,
+    row_idx: usize,
+    expected: &str,
+) -> Result<T, SQLiteSourceError> {
+    T::column_result(value).map_err(|e| {
+        // Described the way `Row::get` would describe it
+        let e = match e {
+            FromSqlError::OutOfRange(i) => rusqlite::Error::IntegralValueOutOfRange(col, i),
+            FromSqlError::Other(err) => {
+                rusqlite::Error::FromSqlConversionFailure(col, value.data_type(), err)
+            }
+            _ => rusqlite::Error::InvalidColumnType(
+                col,
+                stmt.column_name(col)
+                    .map_or_else(|_| col.to_string(), str::to_string),
+                value.data_type(),
+            ),
+        };
+        conversion_error(stmt, value, col, row_idx, expected, e.to_string())
+    })
+}
+
+/// Describe a `value` at `col` that could not be read as `expected`.
+fn conversion_error(
+    stmt: &Statement<'_>,
+    value: ValueRef<'_>,
+    col: usize,
+    row_idx: usize,
+    expected: &str,
+    reason: String,
+) -> SQLiteSourceError {
+    const PREVIEW_CHARS: usize = 40;
+    let preview = match value {
+        ValueRef::Null => "NULL".to_string(),
+        ValueRef::Integer(v) => v.to_string(),
+        ValueRef::Real(v) => v.to_string(),
+        ValueRef::Text(t) => {
+            let t = String::from_utf8_lossy(t);
+            match t.char_indices().nth(PREVIEW_CHARS) {
+                Some((end, _)) => format!("'{}…'", &t[..end]),
+                None => format!("'{t}'"),
+            }
+        }
+        ValueRef::Blob(b) => format!("<{} byte blob>", b.len()),
+    };
+    SQLiteSourceError::Conversion(Box::new(ConversionError {
+        db_path: String::new(),
+        query: String::new(),
+        column: stmt
+            .column_name(col)
+            .map_or_else(|_| col.to_string(), str::to_string),
+        row: row_idx,
+        value: preview,
+        decl_type: stmt
+            .columns()
+            .get(col)
+            .and_then(|c| c.decl_type().map(str::to_string)),
+        expected: expected.to_string(),
+        actual: match value.data_type() {
+            Type::Null => "null",
+            Type::Integer => "integer",
+            Type::Real => "real",
+            Type::Text => "text",
+            Type::Blob => "blob",
+        }
+        .to_string(),
+        reason,
+    }))
+}
+
+/// Narrow an SQLite integer to the declared column width, reporting where it did not fit.
+fn narrow_int<T: TryFrom<i64>>(
+    stmt: &Statement<'_>,
+    raw: ValueRef<'_>,
+    col: usize,
+    row_idx: usize,
+    value: i64,
+    expected: &str,
+) -> Result<T, SQLiteSourceError> {
+    T::try_from(value).map_err(|_| {
+        conversion_error(
+            stmt,
+            raw,
+            col,
+            row_idx,
+            expected,
+            "out of range".to_string(),
+        )
+    })
+}
+
+macro_rules! impl_produce_int {
+    ($($t: ty,)+) => {
+        $(
+            impl<'r, 'a> Produce<'r, $t> for SQLiteSourcePartitionParser<'a> {
+                type Error = SQLiteSourceError;
+
+                #[throws(SQLiteSourceError)]
+                fn produce(&'r mut self) -> $t {
+                    let row_idx = self.rows_fetched.saturating_sub(1);
+                    let (stmt, value, col) = self.next_value()?;
+                    let val: i64 = get_value(stmt, value, col, row_idx, stringify!($t))?;
+                    narrow_int(stmt, value, col, row_idx, val, stringify!($t))?
+                }
+            }
+
+            impl<'r, 'a> Produce<'r, Option<$t>> for SQLiteSourcePartitionParser<'a> {
+                type Error = SQLiteSourceError;
+
+                #[throws(SQLiteSourceError)]
+                fn produce(&'r mut self) -> Option<$t> {
+                    let row_idx = self.rows_fetched.saturating_sub(1);
+                    let (stmt, value, col) = self.next_value()?;
+                    let val: Option<i64> = get_value(stmt, value, col, row_idx, stringify!($t))?;
+                    match val {
+                        Some(v) => Some(narrow_int(stmt, value, col, row_idx, v, stringify!($t))?),
+                        None => None,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_produce_int!(i32, i16, i8, u64, u32, u16, u8,);
+
+/// Read a decimal stored as an integer, real or text, honouring a `DECIMAL_INT` column's scale.
+fn parse_decimal(
+    stmt: &Statement<'_>,
+    col: usize,
+    row_idx: usize,
+    value: ValueRef<'_>,
+    decl: Option<DecimalDecl>,
+) -> Result<Decimal, SQLiteSourceError> {
+    let parsed = match value {
+        ValueRef::Integer(v) => match decl {
+            Some(d) if d.scaled_integer => {
+                Decimal::try_from_i128_with_scale(v as i128, d.scale as u32).ok()
+            }
+            _ => Some(Decimal::from(v)),
+        },
+        // Shortest round-trip formatting recovers the literal that was stored as REAL
+        ValueRef::Real(v) => Decimal::from_str(&v.to_string()).ok(),
+        ValueRef::Text(t) => match std::str::from_utf8(t).map(str::trim) {
+            // Parsing would round away the digits that don't fit
+            Ok(t) if significant_digits(t) > DecimalDecl::MAX_PRECISION as usize => {
+                return Err(conversion_error(
+                    stmt,
+                    value,
+                    col,
+                    row_idx,
+                    "Decimal",
+                    format!(
+                        "more than {} significant digits can't be read exactly",
+                        DecimalDecl::MAX_PRECISION
+                    ),
+                ));
+            }
+            Ok(t) => Decimal::from_str(t)
+                .or_else(|_| Decimal::from_scientific(t))
+                .ok(),
+            Err(_) => None,
+        },
+        ValueRef::Null | ValueRef::Blob(_) => None,
+    };
+    parsed.ok_or_else(|| {
+        conversion_error(
+            stmt,
+            value,
+            col,
+            row_idx,
+            "Decimal",
+            "not a valid decimal".to_string(),
+        )
+    })
+}
+
+/// The digits of a decimal literal's mantissa, leaving out leading zeros and trailing zeros
+/// after the point.
+fn significant_digits(literal: &str) -> usize {
+    let mantissa = literal.split(['e', 'E']).next().unwrap_or_default();
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |s: &str| s.chars().filter(char::is_ascii_digit).collect::<String>();
+    let digits = digits(int) + digits(frac).trim_end_matches('0');
+    digits.trim_start_matches('0').len()
+}
+
+impl<'r, 'a> Produce<'r, Decimal> for SQLiteSourcePartitionParser<'a> {
+    type Error = SQLiteSourceError;
+
+    #[throws(SQLiteSourceError)]
+    fn produce(&'r mut self) -> Decimal {
+        let row_idx = self.rows_fetched.saturating_sub(1);
+        let decl = self.decimals.get(self.current_col).copied().flatten();
+        let (stmt, value, col) = self.next_value()?;
+        parse_decimal(stmt, col, row_idx, value, decl)?
+    }
+}
+
+impl<'r, 'a> Produce<'r, Option<Decimal>> for SQLiteSourcePartitionParser<'a> {
+    type Error = SQLiteSourceError;
+
+    #[throws(SQLiteSourceError)]
+    fn produce(&'r mut self) -> Option<Decimal> {
+        let row_idx = self.rows_fetched.saturating_sub(1);
+        let decl = self.decimals.get(self.current_col).copied().flatten();
+        let (stmt, value, col) = self.next_value()?;
+        match value {
+            ValueRef::Null => None,
+            value => Some(parse_decimal(stmt, col, row_idx, value, decl)?),
+        }
+    }
+}
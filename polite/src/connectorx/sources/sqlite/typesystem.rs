@@ -2,6 +2,7 @@ use super::errors::SQLiteSourceError;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use fehler::{throw, throws};
 use rusqlite::types::Type;
+use rust_decimal::Decimal;
 use std::convert::TryFrom;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -10,7 +11,13 @@ pub enum SQLiteTypeSystem {
     Int8(bool),
     Int4(bool),
     Int2(bool),
+    Int1(bool),
+    UInt8(bool),
+    UInt4(bool),
+    UInt2(bool),
+    UInt1(bool),
     Real(bool),
+    Decimal(bool),
     Text(bool),
     Date(bool),
     Time(bool),
@@ -25,7 +32,13 @@ impl_typesystem! {
         { Int8 => i64 }
         { Int4 => i32 }
         { Int2 => i16 }
+        { Int1 => i8 }
+        { UInt8 => u64 }
+        { UInt4 => u32 }
+        { UInt2 => u16 }
+        { UInt1 => u8 }
         { Real => f64 }
+        { Decimal => Decimal }
         { Text => Box<str> }
         { Date => NaiveDate}
         { Time => NaiveTime}
@@ -59,15 +72,40 @@ impl TryFrom<(Option<&str>, Type)> for SQLiteTypeSystem {
         match types {
             // derive from column's declare type, some rules refer to:
             // https://www.sqlite.org/datatype3.html#affname
+            (Some(decl_type), _) if DecimalDecl::parse(decl_type).is_some() => Decimal(true),
             (Some(decl_type), ty) => {
                 let decl_type = decl_type.to_lowercase();
                 match decl_type.as_str() {
-                    "int4" => Int4(true),
-                    "int2" => Int2(true),
+                    "int4" | "int32" | "mediumint" => Int4(true),
+                    "int2" | "int16" | "smallint" => Int2(true),
+                    "int1" | "tinyint" => Int1(true),
+                    // `UINT<n>` counts bits as Arrow does, whereas `INT2`/`INT4`/`INT8`
+                    // count bytes as Postgres does
+                    "uint8" => UInt1(true),
+                    "uint16" => UInt2(true),
+                    "uint32" => UInt4(true),
+                    "uint64" => UInt8(true),
+                    _ if decl_type.contains("int") && decl_type.contains("unsigned") => {
+                        if decl_type.contains("tinyint") {
+                            UInt1(true)
+                        } else if decl_type.contains("smallint") {
+                            UInt2(true)
+                        } else if decl_type.contains("big") {
+                            UInt8(true)
+                        } else if decl_type.contains("mediumint")
+                            || decl_type.split_whitespace().any(|w| w == "int")
+                        {
+                            UInt4(true)
+                        } else {
+                            UInt8(true)
+                        }
+                    }
                     "boolean" | "bool" => Bool(true),
                     "date" => Date(true),
                     "time" => Time(true),
                     "datetime" | "timestamp" => Timestamp(true),
+                    // JSON documents are read as text and decoded after the transfer
+                    "json" => Text(true),
                     _ if decl_type.contains("int") => Int8(true),
                     _ if decl_type.contains("char")
                         || decl_type.contains("clob")
@@ -90,3 +128,65 @@ impl TryFrom<(Option<&str>, Type)> for SQLiteTypeSystem {
         }
     }
 }
+
+impl SQLiteTypeSystem {
+    /// The same type with any narrow or unsigned integer replaced by `Int8` (i64), which
+    /// is how every integer column was read before declared widths were honoured.
+    pub fn widened(self) -> Self {
+        use SQLiteTypeSystem::*;
+        match self {
+            Int4(n) | Int2(n) | Int1(n) | UInt8(n) | UInt4(n) | UInt2(n) | UInt1(n) => Int8(n),
+            ty => ty,
+        }
+    }
+}
+
+/// Precision and scale of a column declared as `DECIMAL(p,s)` or `NUMERIC(p,s)`.
+///
+/// polite writes exact decimals under two further names: `DECIMAL_TEXT(p,s)` (TEXT affinity,
+/// so SQLite keeps the digits as written) and `DECIMAL_INT(p,s)`, whose integers are the
+/// value scaled by `10^s`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DecimalDecl {
+    pub precision: u8,
+    pub scale: u8,
+    pub scaled_integer: bool,
+}
+
+impl DecimalDecl {
+    /// Most significant digits a value can be read with exactly, bounded by `rust_decimal`'s
+    /// 96-bit mantissa. Values of a wider column that have more digits fail to convert. It is
+    /// also the largest scale a value can have, so wider scales are read at this one.
+    pub const MAX_PRECISION: u8 = 28;
+    /// Widest precision of an Arrow or Polars decimal. Wider declarations are read at this
+    /// precision.
+    pub const MAX_DECLARED_PRECISION: u8 = 38;
+
+    pub fn parse(decl_type: &str) -> Option<Self> {
+        let decl_type = decl_type.to_lowercase();
+        let (name, args) = decl_type.split_once('(')?;
+        let scaled_integer = match name.trim() {
+            "decimal" | "numeric" | "decimal_text" => false,
+            "decimal_int" => true,
+            _ => return None,
+        };
+        let args = args.trim().strip_suffix(')')?;
+        let (precision, scale): (u8, u8) = match args.split_once(',') {
+            Some((p, s)) => (p.trim().parse().ok()?, s.trim().parse().ok()?),
+            None => (args.trim().parse().ok()?, 0),
+        };
+        if precision == 0 || scale > precision {
+            return None;
+        }
+        // A `DECIMAL_INT` integer scaled by more than 10^28 has no exact decimal value, so the
+        // column is read as plain integers
+        if scaled_integer && scale > Self::MAX_PRECISION {
+            return None;
+        }
+        Some(Self {
+            precision: precision.min(Self::MAX_DECLARED_PRECISION),
+            scale: scale.min(Self::MAX_PRECISION),
+            scaled_integer,
+        })
+    }
+}
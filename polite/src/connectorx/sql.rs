@@ -243,6 +243,22 @@ pub fn count_query<T: Dialect>(sql: &CXQuery<String>, dialect: &T) -> CXQuery<St
     CXQuery::Wrapped(tsql)
 }
 
+/// Whether `sql` parses as a single query, which [`count_query`] and [`limit1_query`] can
+/// rewrite. Other statements that return rows (`PRAGMA`, `INSERT ... RETURNING`, a bare
+/// `VALUES` list, SQL that sqlparser doesn't understand) have to be run verbatim.
+pub fn is_rewritable<T: Dialect>(sql: &CXQuery<String>, dialect: &T) -> bool {
+    match Parser::parse_sql(dialect, sql.as_str()) {
+        // SQLite doesn't take a LIMIT directly after VALUES
+        Ok(ast) => {
+            matches!(ast.as_slice(), [Statement::Query(q)] if !matches!(*q.body, SetExpr::Values(_)))
+        }
+        Err(e) => {
+            debug!("parser error: {:?}, running the query verbatim", e);
+            false
+        }
+    }
+}
+
 #[throws(ConnectorXError)]
 pub fn limit1_query<T: Dialect>(sql: &CXQuery<String>, dialect: &T) -> CXQuery<String> {
     trace!("Incoming query: {}", sql);
@@ -526,3 +542,366 @@ pub fn get_partition_range_query_sep<T: Dialect>(
     );
     (sql_min, sql_max)
 }
+
+/// The column `col` of the derived table `table`, quoted.
+fn quoted_column(table: &str, col: &str) -> Expr {
+    Expr::CompoundIdentifier(vec![
+        Ident {
+            value: table.to_string(),
+            quote_style: None,
+        },
+        Ident {
+            value: col.to_string(),
+            quote_style: Some('"'),
+        },
+    ])
+}
+
+/// Restrict `sql` to the rows whose `col` is in `[lower, upper)`. A missing bound leaves that
+/// side open, and a partition with no lower bound also takes the rows where `col` is NULL, so
+/// that consecutive ranges from the first to the last cover every row exactly once.
+#[throws(ConnectorXError)]
+pub fn range_partition_query<T: Dialect>(
+    sql: &str,
+    col: &str,
+    lower: Option<Value>,
+    upper: Option<Value>,
+    dialect: &T,
+) -> String {
+    trace!("Incoming query: {}", sql);
+    const PART_TMP_TAB_NAME: &str = "CXTMPTAB_PART";
+
+    let cid = Box::new(quoted_column(PART_TMP_TAB_NAME, col));
+    let lb = lower.map(|lower| Expr::BinaryOp {
+        left: Box::new(Expr::Value(lower)),
+        op: BinaryOperator::LtEq,
+        right: cid.clone(),
+    });
+    let ub = upper.map(|upper| Expr::BinaryOp {
+        left: cid.clone(),
+        op: BinaryOperator::Lt,
+        right: Box::new(Expr::Value(upper)),
+    });
+    let selection = match (lb, ub) {
+        (Some(lb), Some(ub)) => Some(Expr::BinaryOp {
+            left: Box::new(lb),
+            op: BinaryOperator::And,
+            right: Box::new(ub),
+        }),
+        (Some(lb), None) => Some(lb),
+        (None, Some(ub)) => Some(Expr::Nested(Box::new(Expr::BinaryOp {
+            left: Box::new(ub),
+            op: BinaryOperator::Or,
+            right: Box::new(Expr::IsNull(cid)),
+        }))),
+        (None, None) => None,
+    };
+
+    let tsql = match Parser::parse_sql(dialect, sql) {
+        Ok(ast) => {
+            if ast.len() != 1 {
+                throw!(ConnectorXError::SqlQueryNotSupported(sql.to_string()));
+            }
+
+            let mut query = ast[0]
+                .as_query()
+                .ok_or_else(|| ConnectorXError::SqlQueryNotSupported(sql.to_string()))?
+                .clone();
+            if query.limit.is_none() && query.offset.is_none() {
+                // the partitions are read in parallel, so an ORDER BY would not hold anyway
+                query.order_by.clear();
+            }
+
+            let ast_part = wrap_query(
+                &mut query,
+                vec![SelectItem::Wildcard(WildcardAdditionalOptions::default())],
+                selection,
+                PART_TMP_TAB_NAME,
+            );
+            format!("{}", ast_part)
+        }
+        Err(e) => {
+            warn!("parser error: {:?}, manually compose query string", e);
+            match selection {
+                Some(selection) => format!(
+                    "SELECT * FROM ({}) AS {} WHERE {}",
+                    sql, PART_TMP_TAB_NAME, selection
+                ),
+                None => format!("SELECT * FROM ({}) AS {}", sql, PART_TMP_TAB_NAME),
+            }
+        }
+    };
+
+    debug!("Transformed range partition query: {}", tsql);
+    tsql
+}
+
+/// Up to `rows` non-null values of `col` in the result of `sql`, in order. They are taken at
+/// evenly spaced positions of the result rather than at random, so the same data always gives
+/// the same sample.
+#[throws(ConnectorXError)]
+pub fn sample_column_query<T: Dialect>(sql: &str, col: &str, rows: usize, dialect: &T) -> String {
+    trace!("Incoming query: {}", sql);
+    const SAMPLE_TMP_TAB_NAME: &str = "CXTMPTAB_SAMPLE";
+    const NUMBERED_TMP_TAB_NAME: &str = "CXTMPTAB_NUMBERED";
+    const SAMPLE_COL: &str = "cx_sample";
+
+    let sample_col = Ident {
+        value: SAMPLE_COL.to_string(),
+        quote_style: None,
+    };
+    let cid = quoted_column(SAMPLE_TMP_TAB_NAME, col);
+
+    let values = match Parser::parse_sql(dialect, sql) {
+        Ok(ast) => {
+            if ast.len() != 1 {
+                throw!(ConnectorXError::SqlQueryNotSupported(sql.to_string()));
+            }
+
+            let mut query = ast[0]
+                .as_query()
+                .ok_or_else(|| ConnectorXError::SqlQueryNotSupported(sql.to_string()))?
+                .clone();
+            if query.limit.is_none() && query.offset.is_none() {
+                query.order_by.clear();
+            }
+
+            format!(
+                "{}",
+                wrap_query(
+                    &mut query,
+                    vec![SelectItem::ExprWithAlias {
+                        expr: cid.clone(),
+                        alias: sample_col,
+                    }],
+                    Some(Expr::IsNotNull(Box::new(cid))),
+                    SAMPLE_TMP_TAB_NAME,
+                )
+            )
+        }
+        Err(e) => {
+            warn!("parser error: {:?}, manually compose query string", e);
+            format!(
+                "SELECT {c} AS {s} FROM ({sql}) AS {t} WHERE {c} IS NOT NULL",
+                s = SAMPLE_COL,
+                c = cid,
+                t = SAMPLE_TMP_TAB_NAME,
+            )
+        }
+    };
+
+    // Row `n` of `total` is kept when `n * rows / total` steps up, which keeps `rows` of them
+    // (or all, if there are fewer) spread evenly over the result
+    let tsql = format!(
+        "SELECT {s} FROM (SELECT {s}, ROW_NUMBER() OVER () AS cx_row, COUNT(*) OVER () AS cx_rows \
+         FROM ({values}) AS {n}) WHERE cx_row * {rows} / cx_rows > (cx_row - 1) * {rows} / cx_rows \
+         ORDER BY {s}",
+        s = SAMPLE_COL,
+        n = NUMBERED_TMP_TAB_NAME,
+    );
+
+    debug!("Transformed column sample query: {}", tsql);
+    tsql
+}
+
+/// The table `sql` reads, if it is a single unqualified table with no joins.
+pub fn single_table<T: Dialect>(sql: &str, dialect: &T) -> Option<String> {
+    let ast = Parser::parse_sql(dialect, sql).ok()?;
+    let query = match ast.as_slice() {
+        [statement] => statement.as_query()?.clone(),
+        _ => return None,
+    };
+    let select = match *query.body {
+        SetExpr::Select(select) => select,
+        _ => return None,
+    };
+    match select.from.as_slice() {
+        [TableWithJoins {
+            relation: TableFactor::Table {
+                name, args: None, ..
+            },
+            joins,
+        }] if joins.is_empty() => match name.0.as_slice() {
+            [table] => Some(table.value.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Scalar SQLite functions, whose value depends only on the row they are called on.
+const ROW_WISE_FUNCTIONS: &[&str] = &[
+    "abs",
+    "char",
+    "coalesce",
+    "date",
+    "datetime",
+    "format",
+    "glob",
+    "hex",
+    "ifnull",
+    "iif",
+    "instr",
+    "json",
+    "json_extract",
+    "json_type",
+    "julianday",
+    "length",
+    "like",
+    "lower",
+    "ltrim",
+    "nullif",
+    "printf",
+    "quote",
+    "replace",
+    "round",
+    "rtrim",
+    "sign",
+    "strftime",
+    "substr",
+    "substring",
+    "time",
+    "trim",
+    "typeof",
+    "unhex",
+    "unicode",
+    "unixepoch",
+    "upper",
+];
+
+/// Whether `expr` is computed from one row alone, without aggregate or window functions.
+fn is_row_wise(expr: &Expr) -> bool {
+    match expr {
+        Expr::Identifier(_) | Expr::CompoundIdentifier(_) | Expr::Value(_) => true,
+        Expr::Nested(e)
+        | Expr::UnaryOp { expr: e, .. }
+        | Expr::Cast { expr: e, .. }
+        | Expr::Collate { expr: e, .. }
+        | Expr::IsNull(e)
+        | Expr::IsNotNull(e) => is_row_wise(e),
+        Expr::BinaryOp { left, right, .. }
+        | Expr::Like {
+            expr: left,
+            pattern: right,
+            ..
+        } => is_row_wise(left) && is_row_wise(right),
+        Expr::Between {
+            expr, low, high, ..
+        } => is_row_wise(expr) && is_row_wise(low) && is_row_wise(high),
+        Expr::InList { expr, list, .. } => is_row_wise(expr) && list.iter().all(is_row_wise),
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            operand.iter().chain(else_result).all(|e| is_row_wise(e))
+                && conditions.iter().chain(results).all(is_row_wise)
+        }
+        Expr::Function(f) => {
+            f.over.is_none()
+                && !f.distinct
+                && f.order_by.is_empty()
+                && matches!(f.name.0.as_slice(), [name]
+                    if ROW_WISE_FUNCTIONS.contains(&name.value.to_lowercase().as_str()))
+                && f.args.iter().all(|arg| match arg {
+                    FunctionArg::Named {
+                        arg: FunctionArgExpr::Expr(e),
+                        ..
+                    }
+                    | FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) => is_row_wise(e),
+                    _ => false,
+                })
+        }
+        _ => false,
+    }
+}
+
+/// The table `sql` scans, if each of its rows comes from one row of that table, so that the
+/// scan can be split into rowid ranges: a single table, no CTE, join, aggregate or window
+/// function, DISTINCT, GROUP BY, ORDER BY or LIMIT. A WHERE clause is fine.
+pub fn plain_table_scan<T: Dialect>(sql: &str, dialect: &T) -> Option<String> {
+    let table = single_table(sql, dialect)?;
+    let ast = Parser::parse_sql(dialect, sql).ok()?;
+    let query = ast.first()?.as_query()?;
+    if query.with.is_some()
+        || !query.order_by.is_empty()
+        || query.limit.is_some()
+        || query.offset.is_some()
+        || query.fetch.is_some()
+    {
+        return None;
+    }
+    let select = match &*query.body {
+        SetExpr::Select(select) => select,
+        _ => return None,
+    };
+    if select.distinct.is_some()
+        || select.top.is_some()
+        || select.into.is_some()
+        || !select.group_by.is_empty()
+        || select.having.is_some()
+        || !select.named_window.is_empty()
+        || select.qualify.is_some()
+    {
+        return None;
+    }
+    let row_wise = select.projection.iter().all(|item| match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => is_row_wise(expr),
+        SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => true,
+    });
+    row_wise.then_some(table)
+}
+
+/// Restrict a plain table scan to the rows whose `rowid` (the rowid or an INTEGER PRIMARY KEY
+/// column) is in `[lower, upper)`, adding to its WHERE clause. A missing bound is open.
+#[throws(ConnectorXError)]
+pub fn rowid_range_query<T: Dialect>(
+    sql: &str,
+    rowid: &str,
+    lower: Option<i64>,
+    upper: Option<i64>,
+    dialect: &T,
+) -> String {
+    trace!("Incoming query: {}", sql);
+    let mut ast = Parser::parse_sql(dialect, sql)?;
+    if ast.len() != 1 {
+        throw!(ConnectorXError::SqlQueryNotSupported(sql.to_string()));
+    }
+    let select = match &mut ast[0] {
+        Statement::Query(query) => query.as_select_mut(),
+        _ => None,
+    }
+    .ok_or_else(|| ConnectorXError::SqlQueryNotSupported(sql.to_string()))?;
+
+    let cid = Box::new(Expr::Identifier(Ident {
+        value: rowid.to_string(),
+        quote_style: Some('"'),
+    }));
+    let bound = |v: i64| Box::new(Expr::Value(Value::Number(v.to_string(), false)));
+    let range = [
+        lower.map(|lower| Expr::BinaryOp {
+            left: bound(lower),
+            op: BinaryOperator::LtEq,
+            right: cid.clone(),
+        }),
+        upper.map(|upper| Expr::BinaryOp {
+            left: cid.clone(),
+            op: BinaryOperator::Lt,
+            right: bound(upper),
+        }),
+        select.selection.take().map(|e| Expr::Nested(Box::new(e))),
+    ];
+    select.selection = range
+        .into_iter()
+        .flatten()
+        .reduce(|left, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::And,
+            right: Box::new(right),
+        });
+
+    let tsql = format!("{}", ast[0]);
+    debug!("Transformed rowid range query: {}", tsql);
+    tsql
+}
@@ -7,6 +7,7 @@ use crate::connectorx::{
     typesystem::TypeConversion,
 };
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -32,9 +33,15 @@ impl_transport!(
     mappings = {
         { Bool[bool]                 => Boolean[bool]           | conversion auto }
         { Int8[i64]                  => Int64[i64]              | conversion auto }
-        { Int4[i32]                  => Int64[i64]              | conversion auto }
-        { Int2[i16]                  => Int64[i64]              | conversion auto }
+        { Int4[i32]                  => Int32[i32]              | conversion auto }
+        { Int2[i16]                  => Int16[i16]              | conversion auto }
+        { Int1[i8]                   => Int8[i8]                | conversion auto }
+        { UInt8[u64]                 => UInt64[u64]             | conversion auto }
+        { UInt4[u32]                 => UInt32[u32]             | conversion auto }
+        { UInt2[u16]                 => UInt16[u16]             | conversion auto }
+        { UInt1[u8]                  => UInt8[u8]               | conversion auto }
         { Real[f64]                  => Float64[f64]            | conversion auto }
+        { Decimal[Decimal]           => Decimal[Decimal]        | conversion option }
         { Text[Box<str>]             => LargeUtf8[String]       | conversion option }
         { Blob[Vec<u8>]              => LargeBinary[Vec<u8>]    | conversion auto }
         { Date[NaiveDate]            => Date32[NaiveDate]       | conversion auto }
@@ -48,3 +55,9 @@ impl TypeConversion<Box<str>, String> for SQLiteArrowTransport {
         val.to_string()
     }
 }
+
+impl TypeConversion<Decimal, Decimal> for SQLiteArrowTransport {
+    fn convert(val: Decimal) -> Decimal {
+        val
+    }
+}
@@ -1,10 +1,27 @@
+use crate::blob::{decode_uuid_columns, is_uuid_decl, parse_uuid};
+use crate::cancel::CancellationToken;
+use crate::categorical::apply_categoricals;
+use crate::connectorx::partition::{sqlite_partition, sqlite_rowid_partition, PartitionQuery};
 use crate::connectorx::prelude::*;
+use crate::connectorx::sources::sqlite::{DecimalDecl, SQLiteSourceError};
+use crate::connectorx::transports::SQLiteArrowTransportError;
+use crate::db;
+use crate::error::ConversionError;
+use crate::json::{decode_json_columns, is_json_decl, to_json_text};
+use crate::limit::ReadBudget;
+use crate::normalize::{
+    categories, encode_codes, lookup_table, merge_lookup, CATEGORY_DECL, ENUM_DECL,
+};
+use crate::options::{DecimalStorage, OnLimit, ReadOptions, WriteOptions};
+use crate::progress::{Phase, PROGRESS_CHUNK};
 use crate::types::schema_from_sqlite;
 use crate::PoliteError;
 use polars::prelude::*;
 use rusqlite::types::Value;
-use rusqlite::Connection as SqliteConn;
+use rusqlite::ErrorCode;
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::sync::Arc;
 
 fn save_err(db_path: &str, table: &str, e: rusqlite::Error) -> PoliteError {
     PoliteError::Save {
@@ -14,16 +31,110 @@ fn save_err(db_path: &str, table: &str, e: rusqlite::Error) -> PoliteError {
     }
 }
 
+/// Lift a value conversion failure out of ConnectorX into [`PoliteError::Conversion`], with
+/// the database and query it happened in, and a failed pre-execution statement into
+/// [`PoliteError::PreExecution`].
+fn read_err(db_path: &str, sql: &str, e: ConnectorXOutError) -> PoliteError {
+    let conversion = match e {
+        ConnectorXOutError::SQLiteSourceError(source)
+        | ConnectorXOutError::SQLiteArrowTransportError(SQLiteArrowTransportError::Source(
+            source,
+        )) => source,
+        e => {
+            return PoliteError::Arrow {
+                db_path: db_path.to_string(),
+                source: e,
+            }
+        }
+    };
+    match conversion {
+        SQLiteSourceError::Conversion(mut error) => {
+            error.db_path = db_path.to_string();
+            error.query = sql.to_string();
+            PoliteError::Conversion(error)
+        }
+        SQLiteSourceError::PreExecution { sql, source } => PoliteError::PreExecution {
+            db_path: db_path.to_string(),
+            sql,
+            source: *source,
+        },
+        source => PoliteError::Arrow {
+            db_path: db_path.to_string(),
+            source: source.into(),
+        },
+    }
+}
+
 /// Run a query through ConnectorX and get a Polars DataFrame
 pub fn to_dataframe(db_path: &str, sql: &str) -> Result<DataFrame, PoliteError> {
+    to_dataframe_with_options(db_path, sql, &ReadOptions::default())
+}
+
+/// Run a query through ConnectorX and get a Polars DataFrame, controlling the read with
+/// [`ReadOptions`]
+pub fn to_dataframe_with_options(
+    db_path: &str,
+    sql: &str,
+    options: &ReadOptions,
+) -> Result<DataFrame, PoliteError> {
+    to_dataframe_limited(db_path, sql, options).map(|read| read.df)
+}
+
+/// A read that may have stopped at [`ReadOptions::max_rows`] or [`ReadOptions::max_bytes`].
+#[derive(Debug, Clone)]
+pub struct LimitedRead {
+    pub df: DataFrame,
+    /// Whether rows were left out because the read ran into a limit, with
+    /// [`OnLimit::Truncate`].
+    pub truncated: bool,
+}
+
+/// Like [`to_dataframe_with_options`], but also tells you whether the result was truncated
+/// at a row or byte limit.
+pub fn to_dataframe_limited(
+    db_path: &str,
+    sql: &str,
+    options: &ReadOptions,
+) -> Result<LimitedRead, PoliteError> {
+    let report_phase = |phase| {
+        if let Some(progress) = &options.progress {
+            progress.phase(phase);
+        }
+    };
+
     // Preflight check: validate query with SQLite first
-    let preflight = SqliteConn::open(db_path).map_err(|e| PoliteError::Connect {
+    report_phase(Phase::Preflight);
+    let preflight = db::open(db_path, &options.connect).map_err(|e| PoliteError::Connect {
         db_path: db_path.to_string(),
         source: e,
     })?;
 
+    // Statements the query may depend on, run here first so that failures are reported before
+    // the read pool runs them on each of its connections
+    for pre in &options.pre_execution_queries {
+        preflight
+            .execute_batch(pre)
+            .map_err(|e| PoliteError::PreExecution {
+                db_path: db_path.to_string(),
+                sql: pre.clone(),
+                source: e,
+            })?;
+    }
+
     let stmt = match preflight.prepare(sql) {
         Ok(stmt) => stmt,
+        // Contention is reported as-is so that it can be recognised as retryable
+        Err(e)
+            if matches!(
+                e.sqlite_error_code(),
+                Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+            ) =>
+        {
+            return Err(PoliteError::Exec {
+                sql: sql.to_string(),
+                source: e,
+            });
+        }
         Err(e) => {
             return Err(PoliteError::Query {
                 db_path: db_path.to_string(),
@@ -32,33 +143,127 @@ pub fn to_dataframe(db_path: &str, sql: &str) -> Result<DataFrame, PoliteError>
         }
     };
 
-    // ConnectorX connection
-    let conn = SourceConn::try_from(format!("sqlite://{}", db_path).as_str()).map_err(|e| {
+    let json_columns: Vec<String> = stmt
+        .columns()
+        .iter()
+        .filter(|c| {
+            is_json_decl(c.decl_type()) || options.json_columns.iter().any(|j| j == c.name())
+        })
+        .map(|c| c.name().to_string())
+        .collect();
+    let uuid_columns: Vec<String> = stmt
+        .columns()
+        .iter()
+        .filter(|c| options.decode_uuids && is_uuid_decl(c.decl_type()))
+        .map(|c| c.name().to_string())
+        .collect();
+
+    // ConnectorX connection. The path is percent-encoded so that a URI filename such as
+    // `file:/name?vfs=memdb` isn't parsed as part of the URL, and the source decodes it
+    let encoded = urlencoding::encode(db_path);
+    let conn = SourceConn::try_from(format!("sqlite://{encoded}").as_str()).map_err(|e| {
         PoliteError::Query {
             db_path: db_path.to_string(),
             source: e,
         }
     })?;
 
-    let queries = &[CXQuery::from(sql)];
+    let whole = || vec![CXQuery::from(sql)];
+    let queries = match &options.partition_on {
+        None => match options.auto_partition.ranges(&options.threads) {
+            Some(num) => sqlite_rowid_partition(sql, num, &preflight)
+                .map_err(|e| PoliteError::Partition {
+                    db_path: db_path.to_string(),
+                    column: "rowid".to_string(),
+                    source: e,
+                })?
+                .unwrap_or_else(whole),
+            None => whole(),
+        },
+        Some(on) => {
+            let part =
+                PartitionQuery::new(sql, &on.column, None, None, on.num).with_strategy(on.strategy);
+            sqlite_partition(&part, &preflight).map_err(|e| PoliteError::Partition {
+                db_path: db_path.to_string(),
+                column: on.column.clone(),
+                source: e,
+            })?
+        }
+    };
 
-    // Fetch Arrow batches
-    let arrow = get_arrow(&conn, None, queries, None).map_err(|e| PoliteError::Arrow {
+    // Interrupt every connection the read uses when the caller cancels or the timeout fires
+    let cancel =
+        (options.cancel.is_some() || options.timeout.is_some()).then(|| match &options.cancel {
+            Some(token) => token.child(),
+            None => CancellationToken::new(),
+        });
+    let _timer = cancel
+        .as_ref()
+        .zip(options.timeout)
+        .map(|(token, timeout)| token.cancel_after(timeout));
+    let _preflight = cancel.as_ref().map(|token| token.register(&preflight));
+    let is_cancelled = || cancel.as_ref().is_some_and(|token| token.is_cancelled());
+    let cancelled = |rows: DataFrame| PoliteError::Cancelled {
         db_path: db_path.to_string(),
-        source: e,
-    })?;
+        timed_out: !options.cancel.as_ref().is_some_and(|t| t.is_cancelled()),
+        rows,
+    };
+    let read_options = ReadOptions {
+        cancel: cancel.clone(),
+        ..options.clone()
+    };
+    let budget = (options.max_rows.is_some() || options.max_bytes.is_some())
+        .then(|| Arc::new(ReadBudget::new(options.max_rows, options.max_bytes)));
+
+    // Fetch Arrow batches
+    let arrow = match get_arrow(
+        &conn,
+        None,
+        &queries,
+        Some(&options.pre_execution_queries),
+        &read_options,
+        budget.clone(),
+    ) {
+        Ok(arrow) => arrow,
+        Err(_) if is_cancelled() => {
+            let df = DataFrame::empty_with_schema(&schema_from_sqlite(&stmt, options));
+            let df = apply_categoricals(df, options)
+                .map_err(|e| PoliteError::DataFrame { source: e })?;
+            return Err(cancelled(df));
+        }
+        Err(e) => return Err(read_err(db_path, sql, e)),
+    };
 
     // Convert Arrow → Polars
+    report_phase(Phase::Converting);
     let df = arrow
         .polars()
         .map_err(|e| PoliteError::ArrowToPolars { source: e })?;
 
-    if df.height() == 0 {
-        let schema = schema_from_sqlite(&stmt);
-        return Ok(DataFrame::empty_with_schema(&schema));
-    }
+    let df = if df.height() == 0 {
+        let schema = schema_from_sqlite(&stmt, options);
+        let df = DataFrame::empty_with_schema(&schema);
+        apply_categoricals(df, options).map_err(|e| PoliteError::DataFrame { source: e })?
+    } else {
+        let df = decode_uuid_columns(df, &uuid_columns)?;
+        decode_json_columns(df, &json_columns)
+            .and_then(|df| apply_categoricals(df, options))
+            .map_err(|e| PoliteError::DataFrame { source: e })?
+    };
 
-    Ok(df)
+    if is_cancelled() {
+        return Err(cancelled(df));
+    }
+    match budget.and_then(|b| b.exceeded()) {
+        Some(limit) if options.on_limit == OnLimit::Error => Err(PoliteError::LimitExceeded {
+            db_path: db_path.to_string(),
+            limit,
+        }),
+        exceeded => Ok(LimitedRead {
+            df,
+            truncated: exceeded.is_some(),
+        }),
+    }
 }
 
 /// Insert a Polars DataFrame into a SQLite table.
@@ -68,16 +273,71 @@ pub fn from_dataframe(
     table: &str,
     df: &DataFrame,
 ) -> Result<(), PoliteError> {
+    from_dataframe_with_options(conn, table, df, &WriteOptions::default())
+}
+
+/// Insert a Polars DataFrame into a SQLite table, controlling the write with [`WriteOptions`].
+/// Creates the table if it does not exist.
+pub fn from_dataframe_with_options(
+    conn: &rusqlite::Connection,
+    table: &str,
+    df: &DataFrame,
+    options: &WriteOptions,
+) -> Result<(), PoliteError> {
+    let db_path = match conn.path() {
+        Some(path) if !path.is_empty() => path,
+        _ => ":memory:",
+    };
+    // Register the categories of normalized columns in their lookup tables
+    let mut lookups = HashMap::new();
+    if options.normalize_categoricals {
+        for column in df.get_columns() {
+            if let DataType::Categorical(_, _) | DataType::Enum(_, _) = column.dtype() {
+                let categories =
+                    categories(column).map_err(|e| PoliteError::DataFrame { source: e })?;
+                let ids = merge_lookup(conn, &lookup_table(table, column.name()), &categories)
+                    .map_err(|e| save_err(db_path, table, e))?;
+                lookups.insert(column.name().clone(), ids);
+            }
+        }
+    }
+
     // Build CREATE TABLE statement
     let mut cols_sql = Vec::new();
+    let mut decls = Vec::new();
     for (name, dtype) in df.get_columns().iter().map(|s| (s.name(), s.dtype())) {
+        let owned_type;
         let sql_type = match dtype {
-            DataType::Int64 => "INTEGER",
-            DataType::Float64 => "REAL",
-            DataType::String => "TEXT",
-            _ => "TEXT", // fallback
+            DataType::Categorical(_, _) | DataType::Enum(_, _) if lookups.contains_key(name) => {
+                let decl = match dtype {
+                    DataType::Enum(_, _) => ENUM_DECL,
+                    _ => CATEGORY_DECL,
+                };
+                owned_type = format!(
+                    "{decl} REFERENCES {}(id)",
+                    db::quote_ident(&lookup_table(table, name))
+                );
+                owned_type.as_str()
+            }
+            DataType::String if options.uuid_columns.iter().any(|u| u == name.as_str()) => "UUID",
+            DataType::Decimal(precision, scale)
+                if options.decimal_storage == DecimalStorage::ScaledInteger =>
+            {
+                let precision = precision.unwrap_or(DecimalDecl::MAX_PRECISION as usize);
+                let scale = scale.unwrap_or(0);
+                owned_type = format!("DECIMAL_INT({precision},{scale})");
+                owned_type.as_str()
+            }
+            _ => {
+                owned_type = column_decl(dtype);
+                owned_type.as_str()
+            }
         };
         cols_sql.push(format!("{} {}", name, sql_type));
+        decls.push(sql_type.to_string());
+    }
+    if !options.primary_key.is_empty() {
+        cols_sql.push(format!("PRIMARY KEY ({})", options.primary_key.join(", ")));
     }
     let create_stmt = format!(
         "CREATE TABLE IF NOT EXISTS {} ({})",
@@ -85,26 +345,98 @@ pub fn from_dataframe(
         cols_sql.join(", ")
     );
     conn.execute(&create_stmt, [])
-        .map_err(|e| save_err("<connection>", table, e))?;
+        .map_err(|e| save_err(db_path, table, e))?;
 
     // Build INSERT statement
     let placeholders: Vec<String> = (0..df.width()).map(|_| "?".to_string()).collect();
     let insert_stmt = format!("INSERT INTO {} VALUES ({})", table, placeholders.join(", "));
     let mut insert = conn
         .prepare(&insert_stmt)
-        .map_err(|e| save_err("<connection>", table, e))?;
+        .map_err(|e| save_err(db_path, table, e))?;
+
+    // Integers narrower than 64 bits are all stored as SQLite INTEGER (i64), and categoricals
+    // as their lookup codes or else as plain text
+    let columns = df
+        .get_columns()
+        .iter()
+        .map(|c| match c.dtype() {
+            DataType::Int32
+            | DataType::Int16
+            | DataType::Int8
+            | DataType::UInt32
+            | DataType::UInt16
+            | DataType::UInt8 => c.cast(&DataType::Int64),
+            DataType::Categorical(_, _) | DataType::Enum(_, _) => match lookups.get(c.name()) {
+                Some(ids) => encode_codes(c, ids),
+                None => c.cast(&DataType::String),
+            },
+            _ => Ok(c.clone()),
+        })
+        .collect::<PolarsResult<Vec<_>>>()
+        .map_err(|e| PoliteError::DataFrame { source: e })?;
+
+    let conversion_err = |col_idx: usize, row: usize, value: String, reason: String| {
+        let column = &df.get_columns()[col_idx];
+        PoliteError::Save {
+            db_path: db_path.to_string(),
+            table_name: table.to_string(),
+            source: Box::new(PoliteError::Conversion(Box::new(ConversionError {
+                db_path: db_path.to_string(),
+                query: insert_stmt.clone(),
+                column: column.name().to_string(),
+                row,
+                value,
+                decl_type: Some(decls[col_idx].clone()),
+                expected: "INTEGER".to_string(),
+                actual: column.dtype().to_string(),
+                reason,
+            }))),
+        }
+    };
 
     // Insert each row
     for row_idx in 0..df.height() {
         let mut values: Vec<Value> = Vec::new();
-        for series in df.get_columns() {
+        for (col_idx, series) in columns.iter().enumerate() {
             let val = match series.dtype() {
+                DataType::String
+                    if options
+                        .uuid_columns
+                        .iter()
+                        .any(|u| u == series.name().as_str()) =>
+                {
+                    match series.str().unwrap().get(row_idx) {
+                        Some(v) => match parse_uuid(v) {
+                            Some(bytes) => Value::Blob(bytes.to_vec()),
+                            None => {
+                                return Err(PoliteError::Save {
+                                    db_path: db_path.to_string(),
+                                    table_name: table.to_string(),
+                                    source: Box::new(PoliteError::InvalidUuid {
+                                        column: series.name().to_string(),
+                                        row: row_idx,
+                                        value: v.to_string(),
+                                    }),
+                                })
+                            }
+                        },
+                        None => Value::Null,
+                    }
+                }
                 DataType::Int64 => series
                     .i64()
                     .unwrap()
                     .get(row_idx)
                     .map(|v| v.into())
                     .unwrap_or(Value::Null),
+                DataType::UInt64 => match series.u64().unwrap().get(row_idx) {
+                    Some(v) => i64::try_from(v)
+                        .map_err(|e| {
+                            conversion_err(col_idx, row_idx, v.to_string(), e.to_string())
+                        })?
+                        .into(),
+                    None => Value::Null,
+                },
                 DataType::Float64 => series
                     .f64()
                     .unwrap()
@@ -117,18 +449,86 @@ pub fn from_dataframe(
                     .get(row_idx)
                     .map(|v| v.to_string().into())
                     .unwrap_or(Value::Null),
+                DataType::Binary => series
+                    .binary()
+                    .unwrap()
+                    .get(row_idx)
+                    .map(|v| Value::Blob(v.to_vec()))
+                    .unwrap_or(Value::Null),
+                DataType::Decimal(_, _) => {
+                    let ca = series.decimal().unwrap();
+                    match (ca.get(row_idx), options.decimal_storage) {
+                        (Some(v), DecimalStorage::Text) => format_decimal(v, ca.scale()).into(),
+                        (Some(v), DecimalStorage::ScaledInteger) => i64::try_from(v)
+                            .map_err(|e| {
+                                let value = format_decimal(v, ca.scale());
+                                conversion_err(col_idx, row_idx, value, e.to_string())
+                            })?
+                            .into(),
+                        (None, _) => Value::Null,
+                    }
+                }
+                DataType::List(_) | DataType::Array(_, _) | DataType::Struct(_) => series
+                    .get(row_idx)
+                    .ok()
+                    .and_then(to_json_text)
+                    .map(Value::Text)
+                    .unwrap_or(Value::Null),
                 _ => Value::Null,
             };
             values.push(val);
         }
         insert
             .execute(rusqlite::params_from_iter(values))
-            .map_err(|e| save_err("<connection>", table, e))?;
+            .map_err(|e| save_err(db_path, table, e))?;
+        if let Some(progress) = &options.progress {
+            let written = row_idx + 1;
+            if written % PROGRESS_CHUNK == 0 || written == df.height() {
+                progress.rows_written(table, written, df.height());
+            }
+        }
     }
 
     Ok(())
 }
 
+/// The declared SQLite type a column of `dtype` is written with, by default.
+pub(crate) fn column_decl(dtype: &DataType) -> String {
+    match dtype {
+        DataType::Decimal(precision, scale) => format!(
+            "DECIMAL_TEXT({},{})",
+            precision.unwrap_or(DecimalDecl::MAX_PRECISION as usize),
+            scale.unwrap_or(0)
+        ),
+        DataType::Int64 => "INTEGER".to_string(),
+        // Declared so that reading the table back restores the same width
+        DataType::Int32 => "INT4".to_string(),
+        DataType::Int16 => "SMALLINT".to_string(),
+        DataType::Int8 => "TINYINT".to_string(),
+        DataType::UInt64 => "UINT64".to_string(),
+        DataType::UInt32 => "UINT32".to_string(),
+        DataType::UInt16 => "UINT16".to_string(),
+        DataType::UInt8 => "UINT8".to_string(),
+        DataType::Float64 => "REAL".to_string(),
+        DataType::String => "TEXT".to_string(),
+        DataType::Binary => "BLOB".to_string(),
+        DataType::List(_) | DataType::Array(_, _) | DataType::Struct(_) => "JSON".to_string(),
+        _ => "TEXT".to_string(), // fallback
+    }
+}
+
+/// Render a decimal's scaled integer as exact text, e.g. `(1230, 2)` as `"12.30"`.
+pub(crate) fn format_decimal(v: i128, scale: usize) -> String {
+    let sign = if v < 0 { "-" } else { "" };
+    let digits = v.unsigned_abs().to_string();
+    if scale == 0 {
+        return format!("{sign}{digits}");
+    }
+    let digits = format!("{digits:0>width$}", width = scale + 1);
+    let (int, frac) = digits.split_at(digits.len() - scale);
+    format!("{sign}{int}.{frac}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,4 +556,44 @@ mod tests {
         let val = name_col.str().unwrap().get(0).unwrap();
         assert_eq!(val, "Alice");
     }
+
+    #[test]
+    fn test_stream_read_keeps_declared_widths() {
+        use crate::connectorx::get_arrow::new_record_batch_iter;
+        use arrow::datatypes::DataType as ArrowDataType;
+
+        let db = NamedTempFile::new().unwrap();
+        let db_path = db.path().to_str().unwrap();
+        let conn = rusqlite::Connection::open(db_path).unwrap();
+        conn.execute(
+            "CREATE TABLE t (a TINYINT, b SMALLINT, c INT4, e TINYINT UNSIGNED, f SMALLINT UNSIGNED)",
+            [],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO t VALUES (-1, -2, -3, 4, 5)", [])
+            .unwrap();
+
+        // The batch iterator reads the same dtypes as the arrow destination
+        let source = SourceConn::try_from(format!("sqlite://{db_path}").as_str()).unwrap();
+        let queries = [CXQuery::from("SELECT * FROM t")];
+        let mut batches = new_record_batch_iter(&source, None, &queries, 1024, None, None);
+        batches.prepare();
+        let batch = batches.next_batch().unwrap();
+        let dtypes: Vec<&ArrowDataType> = batch
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|f| f.data_type())
+            .collect();
+        assert_eq!(
+            dtypes,
+            [
+                &ArrowDataType::Int8,
+                &ArrowDataType::Int16,
+                &ArrowDataType::Int32,
+                &ArrowDataType::UInt8,
+                &ArrowDataType::UInt16,
+            ]
+        );
+    }
 }
@@ -1,10 +1,20 @@
+use crate::frames::install_frame;
+use crate::options::ConnectOptions;
 use crate::PoliteError;
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 
 /// Open a SQLite connection
 pub fn connect_sqlite(path: Option<&str>) -> Result<Connection, PoliteError> {
+    connect_sqlite_with_options(path, &ConnectOptions::default())
+}
+
+/// Open a SQLite connection with the given open flags and PRAGMAs
+pub fn connect_sqlite_with_options(
+    path: Option<&str>,
+    options: &ConnectOptions,
+) -> Result<Connection, PoliteError> {
     let db_path = path.unwrap_or(":memory:"); // default if None is passed
-    Connection::open(db_path).map_err(|e| PoliteError::Connect {
+    open(db_path, options).map_err(|e| PoliteError::Connect {
         db_path: db_path.to_string(),
         source: e,
     })
@@ -17,3 +27,84 @@ pub fn execute_query(conn: &Connection, sql: &str) -> Result<usize, PoliteError>
         source: e,
     })
 }
+
+pub(crate) fn open(db_path: &str, options: &ConnectOptions) -> rusqlite::Result<Connection> {
+    let conn = Connection::open_with_flags(db_path, open_flags(options))?;
+    configure(&conn, options)?;
+    Ok(conn)
+}
+
+pub(crate) fn open_flags(options: &ConnectOptions) -> OpenFlags {
+    let mut flags = if options.read_only {
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+    } else if options.create {
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE
+    } else {
+        OpenFlags::SQLITE_OPEN_READ_WRITE
+    };
+    if options.uri {
+        flags |= OpenFlags::SQLITE_OPEN_URI;
+    }
+    if options.no_mutex {
+        flags |= OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    }
+    flags
+}
+
+/// `path` escaped for the path of a `file:` URI, where `?` and `#` would end it and `%` starts
+/// an escape.
+pub(crate) fn uri_path_escape(path: &str) -> String {
+    path.replace('%', "%25")
+        .replace('?', "%3f")
+        .replace('#', "%23")
+}
+
+/// `name` as a double-quoted SQL identifier.
+pub(crate) fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Attach the databases and apply the PRAGMAs, busy timeout, functions and frames in `options`
+/// to an open connection.
+pub(crate) fn configure(conn: &Connection, options: &ConnectOptions) -> rusqlite::Result<()> {
+    // Attached first, so that PRAGMAs such as journal_mode apply to them too
+    for attachment in &options.attached {
+        conn.execute(
+            "ATTACH DATABASE ?1 AS ?2",
+            [attachment.filename(), attachment.alias.clone()],
+        )?;
+    }
+    options.functions.install(conn)?;
+    for (name, df) in &options.frames {
+        install_frame(conn, name, df)?;
+    }
+    if let Some(timeout) = options.busy_timeout {
+        conn.busy_timeout(timeout)?;
+    }
+    let mut pragmas = vec![];
+    if let Some(mode) = options.locking_mode {
+        pragmas.push(format!("PRAGMA locking_mode = {mode};"));
+    }
+    if let Some(mode) = options.journal_mode {
+        pragmas.push(format!("PRAGMA journal_mode = {mode};"));
+    }
+    if let Some(sync) = options.synchronous {
+        pragmas.push(format!("PRAGMA synchronous = {sync};"));
+    }
+    if let Some(on) = options.foreign_keys {
+        pragmas.push(format!(
+            "PRAGMA foreign_keys = {};",
+            if on { "ON" } else { "OFF" }
+        ));
+    }
+    if let Some(size) = options.cache_size {
+        pragmas.push(format!("PRAGMA cache_size = {size};"));
+    }
+    if let Some(size) = options.mmap_size {
+        pragmas.push(format!("PRAGMA mmap_size = {size};"));
+    }
+    if !pragmas.is_empty() {
+        conn.execute_batch(&pragmas.concat())?;
+    }
+    Ok(())
+}
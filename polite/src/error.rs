@@ -1,5 +1,40 @@
+use polars::prelude::DataFrame;
 use thiserror::Error;
 
+/// A value that could not be converted between SQLite and Polars, and where it was found.
+#[derive(Debug, Clone, Error)]
+#[error(
+    "Cannot convert {actual} value {value} in column '{column}' at row {row}{} to {expected} \
+     (query `{query}` on {db_path}): {reason}",
+    decl_type.as_ref().map(|d| format!(" (declared {d})")).unwrap_or_default()
+)]
+pub struct ConversionError {
+    pub db_path: String,
+    /// The query being read, or the statement being written
+    pub query: String,
+    pub column: String,
+    /// Row ordinal, from 0 (within its partition on a partitioned read)
+    pub row: usize,
+    /// The offending value, truncated for display
+    pub value: String,
+    /// The column's declared type, if it has one
+    pub decl_type: Option<String>,
+    /// The type the value had to be converted to
+    pub expected: String,
+    /// The SQLite storage class (`integer`, `real`, `text`, `blob`) or Polars dtype it had
+    pub actual: String,
+    pub reason: String,
+}
+
+/// A limit set in [`ReadOptions`](crate::ReadOptions) that a read ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ReadLimit {
+    #[error("more than {0} row(s)")]
+    Rows(usize),
+    #[error("more than {0} byte(s)")]
+    Bytes(usize),
+}
+
 #[derive(Debug, Error)]
 pub enum PoliteError {
     #[error("ConnectorX Arrow conversion failed on {db_path}: {source}")]
@@ -15,6 +50,23 @@ pub enum PoliteError {
         source: crate::connectorx::destinations::arrow::ArrowDestinationError,
     },
 
+    #[error(
+        "Read from {db_path} {} after {} row(s)",
+        if *timed_out { "timed out" } else { "was cancelled" },
+        rows.height()
+    )]
+    Cancelled {
+        db_path: String,
+        /// Whether the read hit [`ReadOptions::timeout`](crate::ReadOptions) rather than
+        /// being cancelled through its token.
+        timed_out: bool,
+        /// The rows fetched before the read stopped.
+        rows: DataFrame,
+    },
+
+    #[error("Changeset conflict on table '{table}': {conflict}")]
+    ChangesetConflict { table: String, conflict: String },
+
     #[error("Failed to connect to {db_path}: {source}")]
     Connect {
         db_path: String,
@@ -22,6 +74,9 @@ pub enum PoliteError {
         source: rusqlite::Error,
     },
 
+    #[error(transparent)]
+    Conversion(Box<ConversionError>),
+
     #[error("Failed to convert Arrow to DataFrame: {source}")]
     DataFrame {
         #[source]
@@ -35,6 +90,16 @@ pub enum PoliteError {
         source: rusqlite::Error,
     },
 
+    #[error("Value {value} in column '{column}' at row {row} is not a valid UUID")]
+    InvalidUuid {
+        column: String,
+        row: usize,
+        value: String,
+    },
+
+    #[error("Read from {db_path} returned {limit}")]
+    LimitExceeded { db_path: String, limit: ReadLimit },
+
     #[error("Failed to load DataFrame from {db_path}: {source}")]
     Load {
         db_path: String,
@@ -42,6 +107,25 @@ pub enum PoliteError {
         source: Box<dyn std::error::Error + Send + Sync>, // allow wrapping any error
     },
 
+    #[error("No such table or view: {table}")]
+    NoSuchTable { table: String },
+
+    #[error("Failed to partition the read from {db_path} on column '{column}': {source}")]
+    Partition {
+        db_path: String,
+        column: String,
+        #[source]
+        source: crate::connectorx::errors::ConnectorXOutError,
+    },
+
+    #[error("Pre-execution statement `{sql}` failed on {db_path}: {source}")]
+    PreExecution {
+        db_path: String,
+        sql: String,
+        #[source]
+        source: rusqlite::Error,
+    },
+
     #[error("Failed to run query on {db_path}: {source}")]
     Query {
         db_path: String,
@@ -59,7 +143,41 @@ pub enum PoliteError {
 
     #[error("SQLite error: {source}")]
     Sqlite {
-        #[source]
+        #[from]
         source: rusqlite::Error,
     },
+
+    #[error("Invalid watermark on column '{column}' of table '{table}': {reason}")]
+    Watermark {
+        table: String,
+        column: String,
+        reason: String,
+    },
+}
+
+impl PoliteError {
+    /// The SQLite extended result code behind this error, if it came from SQLite, e.g.
+    /// `SQLITE_BUSY` (5) or `SQLITE_CONSTRAINT_UNIQUE` (2067).
+    pub fn sqlite_code(&self) -> Option<i32> {
+        let mut error: Option<&(dyn std::error::Error + 'static)> = Some(self);
+        while let Some(e) = error {
+            if let Some(rusqlite::Error::SqliteFailure(failure, _)) = e.downcast_ref() {
+                return Some(failure.extended_code);
+            }
+            if let Some(failure) = e.downcast_ref::<rusqlite::ffi::Error>() {
+                return Some(failure.extended_code);
+            }
+            error = e.source();
+        }
+        None
+    }
+
+    /// Whether the operation may succeed if retried, because the database was busy or a
+    /// table was locked by another connection.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.sqlite_code().map(|code| code & 0xff),
+            Some(rusqlite::ffi::SQLITE_BUSY | rusqlite::ffi::SQLITE_LOCKED)
+        )
+    }
 }
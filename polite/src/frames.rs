@@ -0,0 +1,347 @@
+//! DataFrames exposed to SQL as read-only virtual tables.
+//!
+//! [`register_frame`] makes a DataFrame queryable under a name on one connection. To `JOIN`
+//! against it in a read, add it to [`ConnectOptions::frames`](crate::ConnectOptions) instead so
+//! that every connection the read opens has it. [`query_frames`] runs SQL over DataFrames
+//! alone.
+//!
+//! Columns are declared with the same SQLite types as [`from_dataframe`](crate::from_dataframe)
+//! writes, so they read back as the same dtypes. Equality constraints (`WHERE id = ?` or a join
+//! on the frame's column) look rows up through a hash index built on first use instead of
+//! scanning the frame.
+
+use crate::dataframe::{column_decl, format_decimal, to_dataframe_with_options};
+use crate::db::quote_ident;
+use crate::json::to_json_text;
+use crate::options::{ConnectOptions, ReadOptions};
+use crate::serialize::memory_db_path;
+use crate::PoliteError;
+use polars::prelude::*;
+use rusqlite::ffi;
+use rusqlite::types::Value;
+use rusqlite::vtab::{
+    read_only_module, Context, CreateVTab, Filters, IndexConstraintOp, IndexInfo, VTab,
+    VTabConnection, VTabCursor, VTabKind,
+};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::ffi::c_int;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Make `df` queryable as the read-only table `temp.<name>` on `conn`, replacing any frame
+/// registered under that name before.
+pub fn register_frame(conn: &Connection, name: &str, df: &DataFrame) -> Result<(), PoliteError> {
+    install_frame(conn, name, df).map_err(PoliteError::from)
+}
+
+/// Run `sql` over the given DataFrames, each queryable under its name, and return the result.
+///
+/// ```rust
+/// use polars::prelude::*;
+/// use polite::frames::query_frames;
+///
+/// let users = df! { "id" => [1_i64, 2], "name" => ["Alice", "Bob"] }.unwrap();
+/// let orders = df! { "user_id" => [2_i64, 2, 1], "total" => [5.0, 7.5, 3.0] }.unwrap();
+/// let totals = query_frames(
+///     "SELECT name, sum(total) AS spent FROM users JOIN orders ON user_id = id GROUP BY name",
+///     &[("users", &users), ("orders", &orders)],
+/// )
+/// .unwrap();
+/// assert_eq!(totals.height(), 2);
+/// ```
+pub fn query_frames(sql: &str, frames: &[(&str, &DataFrame)]) -> Result<DataFrame, PoliteError> {
+    // Reads open their connections on a path, so name an empty in-memory database
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let db_path = memory_db_path(&format!("polite-frames-{n}"));
+    let options = ReadOptions {
+        connect: ConnectOptions {
+            frames: frames
+                .iter()
+                .map(|(name, df)| (name.to_string(), (*df).clone()))
+                .collect(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    to_dataframe_with_options(&db_path, sql, &options)
+}
+
+pub(crate) fn install_frame(conn: &Connection, name: &str, df: &DataFrame) -> rusqlite::Result<()> {
+    // SQLite can't replace a module, so each frame gets its own
+    static NEXT_MODULE: AtomicU64 = AtomicU64::new(0);
+    let module = format!(
+        "polite_frame_{}",
+        NEXT_MODULE.fetch_add(1, Ordering::Relaxed)
+    );
+    let frame = Frame::new(df).map_err(|e| rusqlite::Error::ModuleError(e.to_string()))?;
+    conn.create_module(
+        module.as_str(),
+        read_only_module::<FrameTab>(),
+        Some(Arc::new(frame)),
+    )?;
+    let table = quote_ident(name);
+    conn.execute_batch(&format!(
+        "DROP TABLE IF EXISTS temp.{table}; CREATE VIRTUAL TABLE temp.{table} USING {module}"
+    ))
+}
+
+/// A DataFrame as SQLite sees it.
+struct Frame {
+    df: DataFrame,
+    schema: String,
+    /// For each column, the rows holding each value, built on first lookup
+    indexes: Vec<OnceLock<HashMap<Key, Vec<usize>>>>,
+}
+
+impl Frame {
+    fn new(df: &DataFrame) -> PolarsResult<Self> {
+        // Categoricals are exposed as their text, as `from_dataframe` writes them by default
+        let columns = df
+            .get_columns()
+            .iter()
+            .map(|c| match c.dtype() {
+                DataType::Categorical(_, _) | DataType::Enum(_, _) => c.cast(&DataType::String),
+                _ => Ok(c.clone()),
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+        let mut df = DataFrame::new(columns)?;
+        df.rechunk_mut();
+        let schema = df
+            .get_columns()
+            .iter()
+            .map(|c| format!("{} {}", quote_ident(c.name()), column_decl(c.dtype())))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(Frame {
+            indexes: (0..df.width()).map(|_| OnceLock::new()).collect(),
+            schema: format!("CREATE TABLE x({schema})"),
+            df,
+        })
+    }
+
+    fn value(&self, col: usize, row: usize) -> Value {
+        match self.df.get_columns()[col].get(row) {
+            Ok(value) => to_sql_value(value),
+            Err(_) => Value::Null,
+        }
+    }
+
+    /// Rows whose value in `col` may equal `value`. SQLite rechecks the constraint on each, so
+    /// a few extra rows are harmless but none may be missed.
+    fn lookup(&self, col: usize, value: &Value) -> Vec<usize> {
+        let index = self.indexes[col].get_or_init(|| {
+            let mut index: HashMap<Key, Vec<usize>> = HashMap::new();
+            for row in 0..self.df.height() {
+                if let Some(key) = Key::new(&self.value(col, row)) {
+                    index.entry(key).or_default().push(row);
+                }
+            }
+            index
+        });
+        let mut rows: Vec<usize> = Key::candidates(value)
+            .iter()
+            .filter_map(|key| index.get(key))
+            .flatten()
+            .copied()
+            .collect();
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+}
+
+/// A value as it compares in SQLite: numbers by value whether integer or real.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Num(u64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl Key {
+    fn new(value: &Value) -> Option<Key> {
+        match value {
+            Value::Null => None,
+            Value::Integer(i) => Some(Key::num(*i as f64)),
+            Value::Real(f) => Some(Key::num(*f)),
+            Value::Text(s) => Some(Key::Text(s.clone())),
+            Value::Blob(b) => Some(Key::Blob(b.clone())),
+        }
+    }
+
+    fn num(f: f64) -> Key {
+        // -0.0 and 0.0 are equal
+        Key::Num((f + 0.0).to_bits())
+    }
+
+    /// Keys a constraint value may match once SQLite applies the column's affinity to it.
+    fn candidates(value: &Value) -> Vec<Key> {
+        let mut keys: Vec<Key> = Key::new(value).into_iter().collect();
+        match value {
+            Value::Integer(i) => keys.push(Key::Text(i.to_string())),
+            Value::Real(f) => keys.push(Key::Text(format!("{f:?}"))),
+            Value::Text(s) => keys.extend(s.trim().parse::<f64>().ok().map(Key::num)),
+            _ => {}
+        }
+        keys
+    }
+}
+
+/// The SQLite value a DataFrame cell is exposed as.
+fn to_sql_value(value: AnyValue) -> Value {
+    match value {
+        AnyValue::Null => Value::Null,
+        AnyValue::Boolean(b) => Value::Integer(b as i64),
+        AnyValue::Int8(v) => Value::Integer(v as i64),
+        AnyValue::Int16(v) => Value::Integer(v as i64),
+        AnyValue::Int32(v) => Value::Integer(v as i64),
+        AnyValue::Int64(v) => Value::Integer(v),
+        AnyValue::UInt8(v) => Value::Integer(v as i64),
+        AnyValue::UInt16(v) => Value::Integer(v as i64),
+        AnyValue::UInt32(v) => Value::Integer(v as i64),
+        AnyValue::UInt64(v) => i64::try_from(v).map_or(Value::Real(v as f64), Value::Integer),
+        AnyValue::Float32(v) => Value::Real(v as f64),
+        AnyValue::Float64(v) => Value::Real(v),
+        AnyValue::String(s) => Value::Text(s.to_string()),
+        AnyValue::StringOwned(s) => Value::Text(s.to_string()),
+        AnyValue::Binary(b) => Value::Blob(b.to_vec()),
+        AnyValue::BinaryOwned(b) => Value::Blob(b),
+        AnyValue::Decimal(v, scale) => Value::Text(format_decimal(v, scale)),
+        value @ (AnyValue::List(_)
+        | AnyValue::Array(_, _)
+        | AnyValue::Struct(_, _, _)
+        | AnyValue::StructOwned(_)) => to_json_text(value).map_or(Value::Null, Value::Text),
+        value => Value::Text(value.to_string()),
+    }
+}
+
+#[repr(C)]
+struct FrameTab {
+    /// Base class. Must be first
+    base: ffi::sqlite3_vtab,
+    frame: Arc<Frame>,
+}
+
+unsafe impl<'vtab> VTab<'vtab> for FrameTab {
+    type Aux = Arc<Frame>;
+    type Cursor = FrameCursor<'vtab>;
+
+    fn connect(
+        _db: &mut VTabConnection,
+        aux: Option<&Arc<Frame>>,
+        _args: &[&[u8]],
+    ) -> rusqlite::Result<(String, Self)> {
+        let frame = aux
+            .cloned()
+            .ok_or_else(|| rusqlite::Error::ModuleError("frame module has no frame".into()))?;
+        Ok((
+            frame.schema.clone(),
+            FrameTab {
+                base: ffi::sqlite3_vtab::default(),
+                frame,
+            },
+        ))
+    }
+
+    fn best_index(&self, info: &mut IndexInfo) -> rusqlite::Result<()> {
+        let height = self.frame.df.height().max(1) as f64;
+        // The index matches values exactly, so a comparison under another collation, such as
+        // `name = 'bob' COLLATE NOCASE`, scans instead
+        let mut equality = None;
+        for (i, constraint) in info.constraints().enumerate() {
+            if constraint.is_usable()
+                && constraint.column() >= 0
+                && constraint.operator() == IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_EQ
+                && info.collation(i)? == "BINARY"
+            {
+                equality = Some((i, constraint.column()));
+                break;
+            }
+        }
+        match equality {
+            // Look the value up in the column's index: idx_num is the column, plus one
+            Some((i, col)) => {
+                info.constraint_usage(i).set_argv_index(1);
+                info.set_idx_num(col + 1);
+                info.set_estimated_cost(height.log2() + 1.0);
+                info.set_estimated_rows(1);
+            }
+            None => {
+                info.set_idx_num(0);
+                info.set_estimated_cost(height);
+                info.set_estimated_rows(height as i64);
+            }
+        }
+        Ok(())
+    }
+
+    fn open(&'vtab mut self) -> rusqlite::Result<FrameCursor<'vtab>> {
+        Ok(FrameCursor {
+            base: ffi::sqlite3_vtab_cursor::default(),
+            frame: &self.frame,
+            rows: None,
+            pos: 0,
+        })
+    }
+}
+
+impl CreateVTab<'_> for FrameTab {
+    const KIND: VTabKind = VTabKind::Default;
+}
+
+#[repr(C)]
+struct FrameCursor<'vtab> {
+    /// Base class. Must be first
+    base: ffi::sqlite3_vtab_cursor,
+    frame: &'vtab Frame,
+    /// The rows matching an equality constraint, or `None` to scan every row
+    rows: Option<Vec<usize>>,
+    pos: usize,
+}
+
+impl FrameCursor<'_> {
+    fn row(&self) -> usize {
+        match &self.rows {
+            Some(rows) => rows[self.pos],
+            None => self.pos,
+        }
+    }
+}
+
+unsafe impl VTabCursor for FrameCursor<'_> {
+    fn filter(
+        &mut self,
+        idx_num: c_int,
+        _idx_str: Option<&str>,
+        args: &Filters<'_>,
+    ) -> rusqlite::Result<()> {
+        self.rows = match idx_num {
+            0 => None,
+            col => Some(self.frame.lookup(col as usize - 1, &args.get::<Value>(0)?)),
+        };
+        self.pos = 0;
+        Ok(())
+    }
+
+    fn next(&mut self) -> rusqlite::Result<()> {
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn eof(&self) -> bool {
+        match &self.rows {
+            Some(rows) => self.pos >= rows.len(),
+            None => self.pos >= self.frame.df.height(),
+        }
+    }
+
+    fn column(&self, ctx: &mut Context, i: c_int) -> rusqlite::Result<()> {
+        ctx.set_result(&self.frame.value(i as usize, self.row()))
+    }
+
+    fn rowid(&self) -> rusqlite::Result<i64> {
+        Ok(self.row() as i64)
+    }
+}
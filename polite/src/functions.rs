@@ -0,0 +1,121 @@
+//! Rust functions callable from SQL in polite reads and writes.
+//!
+//! Functions created on your own [`Connection`] aren't visible to reads, which run on their
+//! own connections. Register them in a [`FunctionRegistry`] instead and set it as
+//! [`ConnectOptions::functions`](crate::ConnectOptions), and they are installed on every
+//! connection polite opens with those options.
+//!
+//! ```rust
+//! use polite::functions::{FunctionFlags, FunctionRegistry};
+//!
+//! let functions = FunctionRegistry::new().scalar(
+//!     "slugify",
+//!     1,
+//!     FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+//!     |ctx| Ok(ctx.get::<String>(0)?.to_lowercase().replace(' ', "-")),
+//! );
+//! ```
+
+use rusqlite::functions::SqlFnOutput;
+pub use rusqlite::functions::{Aggregate, Context, FunctionFlags, WindowAggregate};
+use rusqlite::Connection;
+use std::fmt;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Arc;
+
+type Install = Arc<dyn Fn(&Connection) -> rusqlite::Result<()> + Send + Sync>;
+
+/// Scalar, aggregate and window functions to create on each connection. Clones share the same
+/// functions.
+#[derive(Clone, Default)]
+pub struct FunctionRegistry {
+    functions: Vec<(String, Install)>,
+}
+
+impl FunctionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a scalar function taking `n_arg` arguments (`-1` for any number).
+    pub fn scalar<F, T>(mut self, name: &str, n_arg: i32, flags: FunctionFlags, f: F) -> Self
+    where
+        F: Fn(&Context<'_>) -> rusqlite::Result<T> + Send + Sync + 'static,
+        T: SqlFnOutput,
+    {
+        let f = Arc::new(f);
+        let fn_name = name.to_string();
+        let install: Install = Arc::new(move |conn: &Connection| {
+            let f = Arc::clone(&f);
+            conn.create_scalar_function(fn_name.as_str(), n_arg, flags, move |ctx| f(ctx))
+        });
+        self.functions.push((name.to_string(), install));
+        self
+    }
+
+    /// Add an aggregate function, cloning `aggregate` for each connection.
+    pub fn aggregate<A, D, T>(
+        mut self,
+        name: &str,
+        n_arg: i32,
+        flags: FunctionFlags,
+        aggregate: D,
+    ) -> Self
+    where
+        A: RefUnwindSafe + UnwindSafe,
+        D: Aggregate<A, T> + Clone + Send + Sync + 'static,
+        T: SqlFnOutput,
+    {
+        let fn_name = name.to_string();
+        let install: Install = Arc::new(move |conn: &Connection| {
+            conn.create_aggregate_function(fn_name.as_str(), n_arg, flags, aggregate.clone())
+        });
+        self.functions.push((name.to_string(), install));
+        self
+    }
+
+    /// Add an aggregate function that can also be used as a window function, cloning `window`
+    /// for each connection.
+    pub fn window<A, W, T>(
+        mut self,
+        name: &str,
+        n_arg: i32,
+        flags: FunctionFlags,
+        window: W,
+    ) -> Self
+    where
+        A: RefUnwindSafe + UnwindSafe,
+        W: WindowAggregate<A, T> + Clone + Send + Sync + 'static,
+        T: SqlFnOutput,
+    {
+        let fn_name = name.to_string();
+        let install: Install = Arc::new(move |conn: &Connection| {
+            conn.create_window_function(fn_name.as_str(), n_arg, flags, window.clone())
+        });
+        self.functions.push((name.to_string(), install));
+        self
+    }
+
+    /// Names of the registered functions, in the order they were added.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.functions.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Create every registered function on `conn`.
+    pub fn install(&self, conn: &Connection) -> rusqlite::Result<()> {
+        for (_, install) in &self.functions {
+            install(conn)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
@@ -0,0 +1,281 @@
+//! Reading only the rows added or changed since the last read.
+//!
+//! [`read_incremental`] reads the rows of a table past a watermark, the highest value of one
+//! column seen so far, and returns them with the new watermark. The watermark is kept in a
+//! [`WatermarkStore`] between runs, by default the `_polite_watermarks` table of the same
+//! database, so a pipeline can call it repeatedly, and across restarts, picking up where the
+//! last call left off.
+//!
+//! ```rust
+//! use polite::incremental::{read_incremental, WatermarkSpec};
+//! # let tmp = tempfile::NamedTempFile::new().unwrap();
+//! # let db_path = tmp.path().to_str().unwrap();
+//! # let conn = polite::connect_sqlite(Some(db_path)).unwrap();
+//! # conn.execute_batch("CREATE TABLE events (id INTEGER PRIMARY KEY, kind TEXT)").unwrap();
+//!
+//! conn.execute_batch("INSERT INTO events (kind) VALUES ('open'), ('close')").unwrap();
+//! let first = read_incremental(db_path, "events", &WatermarkSpec::rowid()).unwrap();
+//! assert_eq!(first.df.height(), 2);
+//!
+//! conn.execute_batch("INSERT INTO events (kind) VALUES ('open')").unwrap();
+//! let next = read_incremental(db_path, "events", &WatermarkSpec::rowid()).unwrap();
+//! assert_eq!(next.df.height(), 1);
+//! ```
+
+use crate::db::{self, execute_query, quote_ident};
+use crate::options::{ConnectOptions, ReadOptions};
+use crate::table::{read_table, Filter, OrderBy, ReadTableOptions};
+use crate::PoliteError;
+use polars::prelude::DataFrame;
+use rusqlite::types::Value;
+use rusqlite::OptionalExtension;
+use std::fmt;
+use std::sync::Arc;
+
+/// Table [`TableWatermarks`] keeps watermarks in.
+pub const WATERMARK_TABLE: &str = "_polite_watermarks";
+
+/// How the watermark column of a table changes as rows are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkKind {
+    /// The column is the rowid, or an `INTEGER PRIMARY KEY` aliasing it. New rows only.
+    RowId,
+    /// The column only grows as rows are inserted, such as a sequence number or an insertion
+    /// time. New rows only.
+    Monotonic,
+    /// The column is stamped whenever a row is inserted or updated, so changed rows are read
+    /// again. Since a later write can share the last stamp, rows stamped with the watermark
+    /// itself are read again along with the next newer rows, so key the rows you write
+    /// downstream. While nothing is newer than the watermark a call reads no rows.
+    UpdatedAt,
+}
+
+/// The column a table's watermark is taken from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatermarkSpec {
+    pub column: String,
+    pub kind: WatermarkKind,
+}
+
+impl WatermarkSpec {
+    /// The table's rowid.
+    pub fn rowid() -> Self {
+        WatermarkSpec {
+            column: "rowid".to_string(),
+            kind: WatermarkKind::RowId,
+        }
+    }
+
+    pub fn monotonic(column: &str) -> Self {
+        WatermarkSpec {
+            column: column.to_string(),
+            kind: WatermarkKind::Monotonic,
+        }
+    }
+
+    pub fn updated_at(column: &str) -> Self {
+        WatermarkSpec {
+            column: column.to_string(),
+            kind: WatermarkKind::UpdatedAt,
+        }
+    }
+}
+
+/// Where watermarks are kept between reads, keyed by table and column.
+pub trait WatermarkStore: Send + Sync {
+    /// The watermark last saved for `column` of `table`, if any.
+    fn load(&self, table: &str, column: &str) -> Result<Option<Value>, PoliteError>;
+
+    /// Replace the watermark for `column` of `table`.
+    fn save(&self, table: &str, column: &str, watermark: &Value) -> Result<(), PoliteError>;
+}
+
+impl fmt::Debug for dyn WatermarkStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WatermarkStore")
+    }
+}
+
+/// Watermarks kept in the [`WATERMARK_TABLE`] of a SQLite database, which is created on the
+/// first save.
+#[derive(Debug, Clone)]
+pub struct TableWatermarks {
+    db_path: String,
+    connect: ConnectOptions,
+}
+
+impl TableWatermarks {
+    pub fn new(db_path: &str) -> Self {
+        Self::with_options(db_path, &ConnectOptions::default())
+    }
+
+    /// Open the database with `options`, such as the `read.connect` of the
+    /// [`IncrementalOptions`] the store is used with, so a database that needs a busy timeout,
+    /// a `file:` URI or attached databases to be read also has its watermarks kept.
+    pub fn with_options(db_path: &str, options: &ConnectOptions) -> Self {
+        TableWatermarks {
+            db_path: db_path.to_string(),
+            connect: options.clone(),
+        }
+    }
+}
+
+impl WatermarkStore for TableWatermarks {
+    fn load(&self, table: &str, column: &str) -> Result<Option<Value>, PoliteError> {
+        let conn = db::connect_sqlite_with_options(Some(&self.db_path), &self.connect)?;
+        let sql = format!(
+            "SELECT watermark FROM {} WHERE table_name = ?1 AND column_name = ?2",
+            quote_ident(WATERMARK_TABLE)
+        );
+        let exists = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_schema WHERE type = 'table' AND name = ?1",
+                [WATERMARK_TABLE],
+                |_| Ok(()),
+            )
+            .optional()?;
+        if exists.is_none() {
+            return Ok(None);
+        }
+        conn.query_row(&sql, [table, column], |r| r.get(0))
+            .optional()
+            .map_err(|e| PoliteError::Exec { sql, source: e })
+    }
+
+    fn save(&self, table: &str, column: &str, watermark: &Value) -> Result<(), PoliteError> {
+        let conn = db::connect_sqlite_with_options(Some(&self.db_path), &self.connect)?;
+        let name = quote_ident(WATERMARK_TABLE);
+        let create = format!(
+            "CREATE TABLE IF NOT EXISTS {name} (
+                 table_name TEXT NOT NULL,
+                 column_name TEXT NOT NULL,
+                 watermark,
+                 updated_at TEXT NOT NULL,
+                 PRIMARY KEY (table_name, column_name)
+             )"
+        );
+        let upsert = format!(
+            "INSERT INTO {name} VALUES (?1, ?2, ?3, datetime('now'))
+             ON CONFLICT DO UPDATE SET watermark = excluded.watermark,
+                 updated_at = excluded.updated_at"
+        );
+        execute_query(&conn, &create)?;
+        conn.execute(&upsert, rusqlite::params![table, column, watermark])
+            .map_err(|e| PoliteError::Exec {
+                sql: upsert.clone(),
+                source: e,
+            })?;
+        Ok(())
+    }
+}
+
+/// How [`read_incremental_with_options`] finds and keeps the watermark.
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalOptions {
+    /// Read the rows past this watermark rather than the stored one.
+    pub after: Option<Value>,
+    /// Where the watermark is loaded from before the read and saved to after it. Without a
+    /// store nothing is kept, and every row past `after` is read. Build a [`TableWatermarks`]
+    /// with [`TableWatermarks::with_options`] to open the database as `read.connect` says.
+    pub store: Option<Arc<dyn WatermarkStore>>,
+    /// How the rows are read.
+    pub read: ReadOptions,
+}
+
+/// The rows an incremental read found, and the watermark to read past next time.
+#[derive(Debug, Clone)]
+pub struct IncrementalRead {
+    /// The rows past the previous watermark, in watermark order.
+    pub df: DataFrame,
+    /// The highest watermark read so far, or `None` if no row has been read yet.
+    pub watermark: Option<Value>,
+}
+
+/// Read the rows of `table` past the watermark kept in the database's own
+/// [`WATERMARK_TABLE`], and save the new one.
+pub fn read_incremental(
+    db_path: &str,
+    table: &str,
+    spec: &WatermarkSpec,
+) -> Result<IncrementalRead, PoliteError> {
+    let options = IncrementalOptions {
+        store: Some(Arc::new(TableWatermarks::new(db_path))),
+        ..Default::default()
+    };
+    read_incremental_with_options(db_path, table, spec, &options)
+}
+
+/// Read the rows of `table` past a watermark, keeping it as `options` say.
+///
+/// The highest value of the column is taken before the rows are read, and only rows up to it
+/// are read, so a row written during the read is left for the next one rather than skipped.
+/// The new watermark is saved only once the read has succeeded.
+pub fn read_incremental_with_options(
+    db_path: &str,
+    table: &str,
+    spec: &WatermarkSpec,
+    options: &IncrementalOptions,
+) -> Result<IncrementalRead, PoliteError> {
+    let previous = match (&options.after, &options.store) {
+        (Some(after), _) => Some(after.clone()),
+        (None, Some(store)) => store.load(table, &spec.column)?,
+        (None, None) => None,
+    };
+
+    let conn = db::open(db_path, &options.read.connect).map_err(|e| PoliteError::Connect {
+        db_path: db_path.to_string(),
+        source: e,
+    })?;
+    let sql = format!(
+        "SELECT max({}) FROM {}",
+        quote_ident(&spec.column),
+        quote_ident(table)
+    );
+    let high: Value = conn
+        .query_row(&sql, [], |r| r.get(0))
+        .map_err(|e| PoliteError::Exec { sql, source: e })?;
+    if spec.kind == WatermarkKind::RowId && !matches!(high, Value::Integer(_) | Value::Null) {
+        return Err(PoliteError::Watermark {
+            table: table.to_string(),
+            column: spec.column.clone(),
+            reason: "a rowid watermark must be an integer".to_string(),
+        });
+    }
+
+    // An empty table has a NULL maximum, which no row is at or below, so the read still
+    // returns the table's columns
+    let mut filter = vec![Filter::lt_eq(&spec.column, high.clone())];
+    if let Some(previous) = &previous {
+        filter.push(match spec.kind {
+            // Rows at the watermark are only worth reading again once a newer stamp exists, as
+            // a write sharing the last stamp is then read with it rather than on every call
+            WatermarkKind::UpdatedAt if *previous != high => {
+                Filter::gt_eq(&spec.column, previous.clone())
+            }
+            WatermarkKind::UpdatedAt => Filter::gt(&spec.column, previous.clone()),
+            WatermarkKind::RowId | WatermarkKind::Monotonic => {
+                Filter::gt(&spec.column, previous.clone())
+            }
+        });
+    }
+    let df = read_table(
+        db_path,
+        table,
+        &ReadTableOptions {
+            filter,
+            order_by: vec![OrderBy::asc(&spec.column)],
+            read: options.read.clone(),
+            ..Default::default()
+        },
+    )?;
+
+    let watermark = if df.height() > 0 {
+        Some(high)
+    } else {
+        previous
+    };
+    if let (Some(store), Some(watermark)) = (&options.store, &watermark) {
+        store.save(table, &spec.column, watermark)?;
+    }
+    Ok(IncrementalRead { df, watermark })
+}
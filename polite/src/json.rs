@@ -0,0 +1,77 @@
+//! JSON text columns: decoding into nested Polars columns and encoding them back.
+
+use polars::prelude::*;
+use serde_json::Value as Json;
+
+/// Whether a declared column type marks the column as holding JSON documents.
+pub(crate) fn is_json_decl(decl_type: Option<&str>) -> bool {
+    decl_type.is_some_and(|d| d.trim().eq_ignore_ascii_case("json"))
+}
+
+/// Parse the named string columns as JSON, inferring a `Struct`/`List` dtype from all rows.
+pub(crate) fn decode_json_columns(
+    mut df: DataFrame,
+    columns: &[String],
+) -> PolarsResult<DataFrame> {
+    for name in columns {
+        let column = df.column(name)?;
+        // Text may have been dictionary-encoded if every text column was read as Categorical
+        if !matches!(
+            column.dtype(),
+            DataType::String | DataType::Categorical(_, _)
+        ) {
+            continue;
+        }
+        let decoded = column
+            .cast(&DataType::String)?
+            .str()?
+            .json_decode(None, None)?;
+        df.replace(name, decoded)?;
+    }
+    Ok(df)
+}
+
+/// Serialize a nested Polars value as JSON text that SQLite's JSON1 functions accept.
+pub(crate) fn to_json_text(value: AnyValue<'_>) -> Option<String> {
+    match value {
+        AnyValue::Null => None,
+        value => Some(to_json(value).to_string()),
+    }
+}
+
+fn to_json(value: AnyValue<'_>) -> Json {
+    match value.into_static() {
+        AnyValue::Null => Json::Null,
+        AnyValue::Boolean(v) => v.into(),
+        AnyValue::Int8(v) => v.into(),
+        AnyValue::Int16(v) => v.into(),
+        AnyValue::Int32(v) => v.into(),
+        AnyValue::Int64(v) => v.into(),
+        AnyValue::UInt8(v) => v.into(),
+        AnyValue::UInt16(v) => v.into(),
+        AnyValue::UInt32(v) => v.into(),
+        AnyValue::UInt64(v) => v.into(),
+        AnyValue::Float32(v) => {
+            serde_json::Number::from_f64(v.into()).map_or(Json::Null, Json::Number)
+        }
+        AnyValue::Float64(v) => serde_json::Number::from_f64(v).map_or(Json::Null, Json::Number),
+        AnyValue::StringOwned(v) => v.as_str().into(),
+        AnyValue::List(s) | AnyValue::Array(s, _) => Json::Array(
+            (0..s.len())
+                .map(|i| s.get(i).map_or(Json::Null, to_json))
+                .collect(),
+        ),
+        AnyValue::StructOwned(payload) => {
+            let (values, fields) = *payload;
+            Json::Object(
+                fields
+                    .iter()
+                    .map(|f| f.name().to_string())
+                    .zip(values.into_iter().map(to_json))
+                    .collect(),
+            )
+        }
+        // Dates, decimals and the like keep their display form
+        other => other.to_string().into(),
+    }
+}
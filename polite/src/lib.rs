@@ -28,20 +28,73 @@
 //!
 //! ## Modules
 //!
+//! - [`blob`] - UUID and display encodings for blob columns
+//! - [`cancel`] - Cancelling long-running reads
+//! - [`catalog`] - Several databases queried together under aliases
 //! - [`dataframe`] - Functions for converting between DataFrames and SQLite
 //! - [`db`] - Database connection utilities
 //! - [`error`] - Custom error types
+//! - [`frames`] - DataFrames queryable from SQL as virtual tables
+//! - [`functions`] - Rust functions callable from SQL
+//! - [`incremental`] - Reading only rows added or changed since the last read
+//! - [`normalize`] - Lookup tables for Categorical/Enum columns
+//! - [`options`] - Options controlling reads and writes
+//! - [`progress`] - Progress callbacks for long reads and writes
+//! - [`schema`] - Tables, columns, indexes and foreign keys as DataFrames
+//! - [`serialize`] - Databases as bytes, and in memory
+//! - `session` - Capturing and applying changesets (with the `session` feature)
+//! - [`table`] - Reading tables without writing SQL
 
+pub mod blob;
+pub mod cancel;
+pub mod catalog;
+pub(crate) mod categorical;
 mod connectorx;
 pub mod dataframe;
 pub mod db;
 pub mod error;
+pub mod frames;
+pub mod functions;
+pub mod incremental;
+pub(crate) mod json;
+pub(crate) mod limit;
+pub mod normalize;
+pub mod options;
+pub mod progress;
+pub mod schema;
+pub mod serialize;
+#[cfg(feature = "session")]
+pub mod session;
+pub mod table;
 pub(crate) mod types;
 
 // Re-export the main entrypoints at crate root
-pub use dataframe::{from_dataframe, to_dataframe};
-pub use db::{connect_sqlite, execute_query};
-pub use error::PoliteError;
+pub use cancel::CancellationToken;
+pub use catalog::Catalog;
+pub use dataframe::{
+    from_dataframe, from_dataframe_with_options, to_dataframe, to_dataframe_limited,
+    to_dataframe_with_options, LimitedRead,
+};
+pub use db::{connect_sqlite, connect_sqlite_with_options, execute_query};
+pub use error::{ConversionError, PoliteError, ReadLimit};
+pub use frames::{query_frames, register_frame};
+pub use functions::FunctionRegistry;
+pub use incremental::{
+    read_incremental, read_incremental_with_options, IncrementalOptions, IncrementalRead,
+    WatermarkKind, WatermarkSpec,
+};
+pub use normalize::read_normalized;
+pub use options::{
+    Attachment, AutoPartition, ConnectOptions, DecimalStorage, OnLimit, PartitionOn,
+    PartitionStrategy, ReadOptions, ReadThreads, WriteOptions,
+};
+pub use progress::{Phase, ProgressObserver};
+pub use schema::{
+    foreign_keys, indexes, list_tables, list_views, table_schema, table_schema_with_options,
+    table_stats,
+};
+pub use serialize::{deserialize_db, deserialize_db_as, memory_db_path, serialize_db};
+pub use table::{read_table, Filter, OrderBy, ReadTableOptions, Sample};
 
 /// Common imports for polite users.
 ///
@@ -59,10 +112,15 @@ pub use error::PoliteError;
 /// from_dataframe(&conn, "backup_users", &df).unwrap();
 /// ```
 pub mod prelude {
-    pub use crate::{connect_sqlite, execute_query, from_dataframe, to_dataframe, PoliteError};
+    pub use crate::{
+        connect_sqlite, connect_sqlite_with_options, execute_query, from_dataframe,
+        from_dataframe_with_options, read_normalized, read_table, to_dataframe,
+        to_dataframe_with_options, CancellationToken, ConnectOptions, PoliteError, ReadOptions,
+        ReadTableOptions, WriteOptions,
+    };
 
     // Convenience functions from lib module:
-    pub use crate::{load_dataframe, save_dataframe};
+    pub use crate::{load_dataframe, save_dataframe, save_dataframe_with_options};
 }
 
 /// Create a DataFrame from a SQLite file with error handling and logging.
@@ -121,9 +179,27 @@ pub fn save_dataframe(
     table_name: &str,
     df: &polars::prelude::DataFrame,
 ) -> Result<(), PoliteError> {
-    let conn = connect_sqlite(Some(db_path))?;
+    save_dataframe_with_options(
+        db_path,
+        table_name,
+        df,
+        &ConnectOptions::default(),
+        &WriteOptions::default(),
+    )
+}
+
+/// Like [`save_dataframe`], opening the database with `connect` (e.g. for a busy timeout or
+/// WAL) and controlling the write with `options`.
+pub fn save_dataframe_with_options(
+    db_path: &str,
+    table_name: &str,
+    df: &polars::prelude::DataFrame,
+    connect: &ConnectOptions,
+    options: &WriteOptions,
+) -> Result<(), PoliteError> {
+    let conn = connect_sqlite_with_options(Some(db_path), connect)?;
 
-    from_dataframe(&conn, table_name, df).map_err(|e| PoliteError::Save {
+    from_dataframe_with_options(&conn, table_name, df, options).map_err(|e| PoliteError::Save {
         db_path: db_path.to_string(),
         table_name: table_name.to_string(),
         source: Box::new(e),
@@ -0,0 +1,71 @@
+//! Row and byte budgets for reads.
+
+use crate::error::ReadLimit;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Rows in each Arrow batch when a byte limit is set, so that it is checked often.
+pub(crate) const BYTE_LIMIT_BATCH_SIZE: usize = 4096;
+
+/// The rows and bytes one read may still fetch, shared by all of its partitions.
+#[derive(Debug, Default)]
+pub(crate) struct ReadBudget {
+    max_rows: Option<usize>,
+    max_bytes: Option<usize>,
+    rows: AtomicUsize,
+    bytes: AtomicUsize,
+    is_exceeded: AtomicBool,
+    exceeded: Mutex<Option<ReadLimit>>,
+}
+
+impl ReadBudget {
+    pub(crate) fn new(max_rows: Option<usize>, max_bytes: Option<usize>) -> Self {
+        ReadBudget {
+            max_rows,
+            max_bytes,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn has_byte_limit(&self) -> bool {
+        self.max_bytes.is_some()
+    }
+
+    /// Claim one more row, or return false if the limit has been reached.
+    pub(crate) fn take_row(&self) -> bool {
+        if self.is_exceeded() {
+            return false;
+        }
+        let taken = self.rows.fetch_add(1, Ordering::Relaxed);
+        match self.max_rows {
+            Some(max) if taken >= max => {
+                self.exceed(ReadLimit::Rows(max));
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Count `bytes` more of fetched data against the limit.
+    pub(crate) fn add_bytes(&self, bytes: usize) {
+        let total = self.bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        match self.max_bytes {
+            Some(max) if total > max => self.exceed(ReadLimit::Bytes(max)),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn is_exceeded(&self) -> bool {
+        self.is_exceeded.load(Ordering::Relaxed)
+    }
+
+    /// The first limit the read ran into, if any.
+    pub(crate) fn exceeded(&self) -> Option<ReadLimit> {
+        *self.exceeded.lock().unwrap()
+    }
+
+    fn exceed(&self, limit: ReadLimit) {
+        self.exceeded.lock().unwrap().get_or_insert(limit);
+        self.is_exceeded.store(true, Ordering::Relaxed);
+    }
+}
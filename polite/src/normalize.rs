@@ -0,0 +1,160 @@
+//! Lookup tables for Categorical/Enum columns.
+//!
+//! With [`WriteOptions::normalize_categoricals`](crate::WriteOptions), each Categorical or
+//! Enum column `col` of `table` is stored as integer codes referencing a dictionary table
+//! `table__col(id INTEGER PRIMARY KEY, value TEXT UNIQUE)`. [`read_normalized`] joins the
+//! codes back and restores the dtypes.
+
+use crate::dataframe::to_dataframe_with_options;
+use crate::db::{self, quote_ident};
+use crate::options::ReadOptions;
+use crate::PoliteError;
+use polars::prelude::*;
+use polars_arrow::array::Utf8ViewArray;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Declared type of a code column for a Categorical.
+pub(crate) const CATEGORY_DECL: &str = "CATEGORY_ID";
+/// Declared type of a code column for an Enum, whose lookup ids follow the category order.
+pub(crate) const ENUM_DECL: &str = "ENUM_ID";
+
+/// Name of the lookup table holding the categories of `column` in `table`.
+pub(crate) fn lookup_table(table: &str, column: &str) -> String {
+    format!("{table}__{column}")
+}
+
+/// The categories to register for a column: every Enum category in order, the categories of
+/// a Categorical's mapping, or else the distinct values in order of appearance.
+pub(crate) fn categories(column: &Column) -> PolarsResult<Vec<String>> {
+    if let DataType::Enum(Some(rev_map), _) | DataType::Categorical(Some(rev_map), _) =
+        column.dtype()
+    {
+        return Ok(rev_map
+            .get_categories()
+            .values_iter()
+            .map(str::to_string)
+            .collect());
+    }
+    let values = column.cast(&DataType::String)?;
+    let seen: PlIndexSet<&str> = values.str()?.iter().flatten().collect();
+    Ok(seen.into_iter().map(str::to_string).collect())
+}
+
+/// Create the lookup table if needed, add any new categories and return the id of each value.
+pub(crate) fn merge_lookup(
+    conn: &Connection,
+    lookup: &str,
+    categories: &[String],
+) -> rusqlite::Result<HashMap<String, i64>> {
+    let lookup = quote_ident(lookup);
+    conn.execute(
+        &format!("CREATE TABLE IF NOT EXISTS {lookup} (id INTEGER PRIMARY KEY, value TEXT UNIQUE)"),
+        [],
+    )?;
+    let mut insert = conn.prepare(&format!(
+        "INSERT OR IGNORE INTO {lookup} (value) VALUES (?)"
+    ))?;
+    for category in categories {
+        insert.execute([category])?;
+    }
+    let mut select = conn.prepare(&format!("SELECT value, id FROM {lookup}"))?;
+    let ids = select
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(ids)
+}
+
+/// Replace a Categorical/Enum column by the lookup ids of its values.
+pub(crate) fn encode_codes(column: &Column, ids: &HashMap<String, i64>) -> PolarsResult<Column> {
+    let values = column.cast(&DataType::String)?;
+    let codes: Int64Chunked = values
+        .str()?
+        .iter()
+        .map(|v| v.and_then(|v| ids.get(v).copied()))
+        .collect();
+    Ok(codes.with_name(column.name().clone()).into_column())
+}
+
+/// Read a table written with `normalize_categoricals`, joining each code column back to its
+/// lookup table and returning it as a `Categorical` or `Enum` column.
+pub fn read_normalized(
+    db_path: &str,
+    table: &str,
+    options: &ReadOptions,
+) -> Result<DataFrame, PoliteError> {
+    let conn = db::open(db_path, &options.connect).map_err(|e| PoliteError::Connect {
+        db_path: db_path.to_string(),
+        source: e,
+    })?;
+    let exec_err = |sql: &str, e| PoliteError::Exec {
+        sql: sql.to_string(),
+        source: e,
+    };
+
+    // Code columns are those with a foreign key to their own lookup table
+    let quoted = quote_ident(table);
+    let fk_sql = format!("PRAGMA foreign_key_list({quoted})");
+    let references: Vec<(String, String)> = conn
+        .prepare(&fk_sql)
+        .and_then(|mut stmt| {
+            stmt.query_map([], |r| Ok((r.get::<_, String>(3)?, r.get::<_, String>(2)?)))?
+                .collect()
+        })
+        .map_err(|e| exec_err(&fk_sql, e))?;
+
+    let info_sql = format!("PRAGMA table_info({quoted})");
+    let columns: Vec<(String, String)> = conn
+        .prepare(&info_sql)
+        .and_then(|mut stmt| {
+            stmt.query_map([], |r| Ok((r.get(1)?, r.get(2)?)))?
+                .collect()
+        })
+        .map_err(|e| exec_err(&info_sql, e))?;
+
+    let mut select = Vec::new();
+    let mut joins = Vec::new();
+    let mut dtypes = Vec::new();
+    for (name, decl) in &columns {
+        let lookup = lookup_table(table, name);
+        let is_code = references
+            .iter()
+            .any(|(from, to)| from == name && *to == lookup);
+        let (lookup_sql, name_sql) = (quote_ident(&lookup), quote_ident(name));
+        if !is_code {
+            select.push(format!("{quoted}.{name_sql}"));
+            continue;
+        }
+        select.push(format!("{lookup_sql}.value AS {name_sql}"));
+        joins.push(format!(
+            "LEFT JOIN {lookup_sql} ON {quoted}.{name_sql} = {lookup_sql}.id"
+        ));
+        let dtype = if decl.eq_ignore_ascii_case(ENUM_DECL) {
+            let sql = format!("SELECT value FROM {lookup_sql} ORDER BY id");
+            let values: Vec<String> = conn
+                .prepare(&sql)
+                .and_then(|mut stmt| stmt.query_map([], |r| r.get(0))?.collect())
+                .map_err(|e| exec_err(&sql, e))?;
+            create_enum_dtype(Utf8ViewArray::from_slice_values(&values))
+        } else {
+            DataType::Categorical(None, Default::default())
+        };
+        dtypes.push((name.clone(), dtype));
+    }
+
+    let sql = format!(
+        "SELECT {} FROM {quoted} {}",
+        select.join(", "),
+        joins.join(" ")
+    );
+    let mut df = to_dataframe_with_options(db_path, &sql, options)?;
+    for (name, dtype) in dtypes {
+        let restored = df
+            .column(&name)
+            .and_then(|c| c.cast(&dtype))
+            .map_err(|e| PoliteError::DataFrame { source: e })?;
+        df.replace(&name, restored.take_materialized_series())
+            .map_err(|e| PoliteError::DataFrame { source: e })?;
+    }
+    Ok(df)
+}
@@ -0,0 +1,478 @@
+//! Options controlling how data is read from and written to SQLite.
+
+use crate::cancel::CancellationToken;
+use crate::functions::FunctionRegistry;
+use crate::progress::ProgressObserver;
+use polars::prelude::DataFrame;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Options for reading a query result into a DataFrame.
+///
+/// Use [`Default`] for the standard behaviour and override only the fields you need:
+///
+/// ```rust
+/// use polite::ReadOptions;
+///
+/// let opts = ReadOptions {
+///     widen_integers: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    /// Read every integer column as `Int64`, ignoring narrower or unsigned declared types
+    /// such as `SMALLINT` or `TINYINT UNSIGNED`.
+    pub widen_integers: bool,
+    /// Columns to parse as JSON into `Struct`/`List` columns, in addition to any declared
+    /// `JSON`. The nested dtype is inferred from every row.
+    pub json_columns: Vec<String>,
+    /// Read 16-byte blobs in columns declared `UUID` or `BLOB(16)` as canonical UUID strings
+    /// such as `"67e55044-10b1-426f-9247-bb680e5fe0c8"`.
+    pub decode_uuids: bool,
+    /// Read every text column as `Categorical`.
+    pub categorical_text: bool,
+    /// Text columns to read as `Categorical`.
+    pub categorical_columns: Vec<String>,
+    /// Text columns to read as `Enum`, each with its categories in order. A value outside the
+    /// categories is an error.
+    pub enum_columns: Vec<(String, Vec<String>)>,
+    /// Also read as `Categorical` any other text column whose number of distinct values,
+    /// relative to its non-null values, is at most this ratio (e.g. `0.01`). The ratio is only
+    /// known once the whole column has been read as `String`, so such a column is cast after
+    /// the read, and still takes its full size in memory during it: name the columns in
+    /// `categorical_columns` to build them as dictionaries while they are read.
+    pub categorical_threshold: Option<f64>,
+    /// Stop the read after this long, returning
+    /// [`PoliteError::Cancelled`](crate::PoliteError::Cancelled) with the rows fetched so far.
+    pub timeout: Option<Duration>,
+    /// Stop the read when this token is cancelled, interrupting the running statement on
+    /// every connection it uses.
+    pub cancel: Option<CancellationToken>,
+    /// Told the phase of the read and the rows each partition has fetched.
+    pub progress: Option<Arc<dyn ProgressObserver>>,
+    /// Stop the read once it returns more than this many rows.
+    pub max_rows: Option<usize>,
+    /// Stop the read once the fetched Arrow data passes this many bytes. It is checked every
+    /// few thousand rows, so the result may run a little over.
+    pub max_bytes: Option<usize>,
+    /// What to do when the read runs into `max_rows` or `max_bytes`.
+    pub on_limit: OnLimit,
+    /// Statements run on every connection the read opens before the query, such as
+    /// `PRAGMA cache_size = -64000`, `ATTACH 'other.db' AS other` or `CREATE TEMP VIEW ...`.
+    /// They run once on the preflight connection and once on each pooled connection, so a
+    /// statement that changes the database itself, such as `CREATE TABLE`, fails the second
+    /// time.
+    pub pre_execution_queries: Vec<String>,
+    /// How the read's connections are opened and configured.
+    pub connect: ConnectOptions,
+    /// Run only the query itself, skipping the `LIMIT 1` probe for the schema and the
+    /// `COUNT(*)` taken for progress reports. Column types come from the first rows fetched
+    /// (up to a batch, until no column is still null), so expensive queries run once.
+    pub single_pass: bool,
+    /// Split the read into ranges of one column, read in parallel over separate connections.
+    /// The rows come back grouped by range rather than in the query's order.
+    pub partition_on: Option<PartitionOn>,
+    /// Split a plain scan of one table (`SELECT ... FROM t WHERE ...`) into rowid ranges read
+    /// in parallel, without naming a column. Other queries, views and `WITHOUT ROWID` tables
+    /// are read in one piece. Ignored when `partition_on` is set.
+    pub auto_partition: AutoPartition,
+    /// The threads a partitioned read fetches its partitions on.
+    pub threads: ReadThreads,
+}
+
+/// The rayon threads a read runs its partitions on. Neither choice other than `Global` touches
+/// rayon's global pool, so polite can share a process with other rayon users.
+#[derive(Debug, Clone, Default)]
+pub enum ReadThreads {
+    /// Rayon's global pool.
+    #[default]
+    Global,
+    /// A pool of this many threads, built for the read and dropped after it.
+    Count(usize),
+    /// A pool the caller owns, e.g. one shared by all reads in a service.
+    Pool(Arc<rayon::ThreadPool>),
+}
+
+impl ReadThreads {
+    /// The pool to run the read in, or `None` for the global pool.
+    pub fn pool(&self) -> Result<Option<Arc<rayon::ThreadPool>>, rayon::ThreadPoolBuildError> {
+        Ok(match self {
+            ReadThreads::Global => None,
+            ReadThreads::Count(num) => Some(Arc::new(
+                rayon::ThreadPoolBuilder::new().num_threads(*num).build()?,
+            )),
+            ReadThreads::Pool(pool) => Some(Arc::clone(pool)),
+        })
+    }
+
+    /// How many threads the read's partitions are fetched on.
+    pub fn num_threads(&self) -> usize {
+        match self {
+            // The pool the caller is running in, which is the global one outside any other.
+            // Rayon sizes a pool built with zero threads the way it sizes the global pool
+            ReadThreads::Global | ReadThreads::Count(0) => rayon::current_num_threads(),
+            ReadThreads::Count(num) => *num,
+            ReadThreads::Pool(pool) => pool.current_num_threads(),
+        }
+    }
+}
+
+/// How many rowid ranges [`ReadOptions::auto_partition`] splits a table scan into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutoPartition {
+    /// Read in one piece.
+    #[default]
+    Off,
+    /// One range per thread of the pool the read runs on (see [`ReadOptions::threads`]).
+    Cores,
+    /// This many ranges.
+    Ranges(usize),
+}
+
+impl AutoPartition {
+    /// The number of ranges, if splitting at all, for a read run on `threads`.
+    pub fn ranges(self, threads: &ReadThreads) -> Option<usize> {
+        match self {
+            AutoPartition::Off => None,
+            AutoPartition::Cores => Some(threads.num_threads()),
+            AutoPartition::Ranges(n) => Some(n),
+        }
+    }
+}
+
+/// A column to split a read on, into `num` ranges chosen by `strategy`.
+///
+/// ```
+/// use polite::{PartitionOn, PartitionStrategy};
+///
+/// let on = PartitionOn::new("created_at", 8).strategy(PartitionStrategy::quantile());
+/// assert_eq!(on.num, 8);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartitionOn {
+    pub column: String,
+    pub num: usize,
+    pub strategy: PartitionStrategy,
+}
+
+impl PartitionOn {
+    pub fn new(column: impl Into<String>, num: usize) -> Self {
+        Self {
+            column: column.into(),
+            num,
+            strategy: PartitionStrategy::default(),
+        }
+    }
+
+    pub fn strategy(mut self, strategy: PartitionStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+}
+
+/// How a partition column's values are split into ranges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PartitionStrategy {
+    /// Ranges of equal width between the column's smallest and largest value. The column must
+    /// hold numbers, or dates or datetimes as text (e.g. `2024-01-31 12:00:00`).
+    #[default]
+    EqualWidth,
+    /// Ranges holding about as many rows each, split at the column's quantiles. They come from
+    /// the `sqlite_stat4` samples of an index on the column when the query reads one table
+    /// that has been `ANALYZE`d, and otherwise from a sample of `sample_rows` values evenly
+    /// spaced through the result, so the same data is always split the same way.
+    /// Works on any column, including text.
+    Quantile { sample_rows: usize },
+}
+
+impl PartitionStrategy {
+    /// Quantiles of a sample of 10,000 values (or `sqlite_stat4`).
+    pub fn quantile() -> Self {
+        PartitionStrategy::Quantile {
+            sample_rows: 10_000,
+        }
+    }
+}
+
+/// What a read does when it runs into [`ReadOptions::max_rows`] or [`ReadOptions::max_bytes`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnLimit {
+    /// Fail with [`PoliteError::LimitExceeded`](crate::PoliteError::LimitExceeded).
+    #[default]
+    Error,
+    /// Return the rows fetched up to the limit. Use
+    /// [`to_dataframe_limited`](crate::to_dataframe_limited) to learn whether that happened.
+    Truncate,
+}
+
+/// How Polars `Decimal` columns are stored when written to SQLite.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecimalStorage {
+    /// Exact decimal text in a `DECIMAL_TEXT(p,s)` column, e.g. `"12.30"`.
+    #[default]
+    Text,
+    /// The value scaled by `10^s` in a `DECIMAL_INT(p,s)` column, e.g. `1230`.
+    ScaledInteger,
+}
+
+/// Options for writing a DataFrame into a SQLite table.
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Storage used for `Decimal` columns. Either way they read back as the same `Decimal(p,s)`.
+    pub decimal_storage: DecimalStorage,
+    /// String columns holding UUIDs to store as 16-byte blobs in a `UUID` column.
+    pub uuid_columns: Vec<String>,
+    /// Store Categorical/Enum columns as integer codes referencing a `table__column` lookup
+    /// table, adding any new categories on append. Read them back with
+    /// [`read_normalized`](crate::read_normalized).
+    pub normalize_categoricals: bool,
+    /// Columns making up the primary key of a table the write creates.
+    pub primary_key: Vec<String>,
+    /// Told how many rows have been written.
+    pub progress: Option<Arc<dyn ProgressObserver>>,
+}
+
+/// How a SQLite connection is opened and configured. Honoured by
+/// [`connect_sqlite_with_options`](crate::connect_sqlite_with_options),
+/// [`save_dataframe_with_options`](crate::save_dataframe_with_options) and, through
+/// [`ReadOptions::connect`], by every connection a read opens.
+///
+/// The default opens the database read-write, creating it if missing, exactly like
+/// [`connect_sqlite`](crate::connect_sqlite), and leaves every PRAGMA at SQLite's default.
+/// Set fields directly or chain the builder methods:
+///
+/// ```rust
+/// use polite::options::{ConnectOptions, JournalMode, Synchronous};
+/// use std::time::Duration;
+///
+/// let opts = ConnectOptions::default()
+///     .journal_mode(JournalMode::Wal)
+///     .synchronous(Synchronous::Normal)
+///     .busy_timeout(Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectOptions {
+    /// Open the database read-only (`SQLITE_OPEN_READONLY`).
+    pub read_only: bool,
+    /// Create the database if it doesn't exist (`SQLITE_OPEN_CREATE`). Ignored when read-only.
+    pub create: bool,
+    /// Interpret the path as a `file:` URI (`SQLITE_OPEN_URI`).
+    pub uri: bool,
+    /// Open in multi-thread mode (`SQLITE_OPEN_NOMUTEX`).
+    pub no_mutex: bool,
+    /// `PRAGMA journal_mode`
+    pub journal_mode: Option<JournalMode>,
+    /// `PRAGMA synchronous`
+    pub synchronous: Option<Synchronous>,
+    /// How long to retry when the database is locked, instead of failing with `SQLITE_BUSY`.
+    pub busy_timeout: Option<Duration>,
+    /// `PRAGMA foreign_keys`
+    pub foreign_keys: Option<bool>,
+    /// `PRAGMA cache_size`: pages if positive, KiB if negative.
+    pub cache_size: Option<i64>,
+    /// `PRAGMA mmap_size`, in bytes.
+    pub mmap_size: Option<i64>,
+    /// `PRAGMA locking_mode`
+    pub locking_mode: Option<LockingMode>,
+    /// Rust functions to create on the connection.
+    pub functions: FunctionRegistry,
+    /// DataFrames to expose as read-only `temp` tables, by name. See [`crate::frames`].
+    pub frames: Vec<(String, DataFrame)>,
+    /// Databases to `ATTACH` under an alias, in order. See [`crate::catalog`].
+    pub attached: Vec<Attachment>,
+}
+
+/// A database file attached to a connection as `alias`, so its tables can be queried as
+/// `alias.table`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub alias: String,
+    pub path: String,
+    /// Attach the file read-only (`mode=ro`), so writes to its tables fail.
+    pub read_only: bool,
+}
+
+impl Attachment {
+    /// The filename `ATTACH` is given, as a `file:` URI when read-only.
+    pub(crate) fn filename(&self) -> String {
+        if !self.read_only {
+            return self.path.clone();
+        }
+        if self.path.starts_with("file:") {
+            let sep = if self.path.contains('?') { '&' } else { '?' };
+            return format!("{}{sep}mode=ro", self.path);
+        }
+        format!("file:{}?mode=ro", crate::db::uri_path_escape(&self.path))
+    }
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        ConnectOptions {
+            read_only: false,
+            create: true,
+            uri: true,
+            no_mutex: true,
+            journal_mode: None,
+            synchronous: None,
+            busy_timeout: None,
+            foreign_keys: None,
+            cache_size: None,
+            mmap_size: None,
+            locking_mode: None,
+            functions: FunctionRegistry::default(),
+            frames: Vec::new(),
+            attached: Vec::new(),
+        }
+    }
+}
+
+impl ConnectOptions {
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn create(mut self, create: bool) -> Self {
+        self.create = create;
+        self
+    }
+
+    pub fn uri(mut self, uri: bool) -> Self {
+        self.uri = uri;
+        self
+    }
+
+    pub fn no_mutex(mut self, no_mutex: bool) -> Self {
+        self.no_mutex = no_mutex;
+        self
+    }
+
+    pub fn journal_mode(mut self, journal_mode: JournalMode) -> Self {
+        self.journal_mode = Some(journal_mode);
+        self
+    }
+
+    pub fn synchronous(mut self, synchronous: Synchronous) -> Self {
+        self.synchronous = Some(synchronous);
+        self
+    }
+
+    pub fn busy_timeout(mut self, busy_timeout: Duration) -> Self {
+        self.busy_timeout = Some(busy_timeout);
+        self
+    }
+
+    pub fn foreign_keys(mut self, foreign_keys: bool) -> Self {
+        self.foreign_keys = Some(foreign_keys);
+        self
+    }
+
+    pub fn cache_size(mut self, cache_size: i64) -> Self {
+        self.cache_size = Some(cache_size);
+        self
+    }
+
+    pub fn mmap_size(mut self, mmap_size: i64) -> Self {
+        self.mmap_size = Some(mmap_size);
+        self
+    }
+
+    pub fn locking_mode(mut self, locking_mode: LockingMode) -> Self {
+        self.locking_mode = Some(locking_mode);
+        self
+    }
+
+    pub fn functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Expose `df` to SQL as the read-only table `temp.<name>`.
+    pub fn frame(mut self, name: impl Into<String>, df: DataFrame) -> Self {
+        self.frames.push((name.into(), df));
+        self
+    }
+
+    /// Attach the database at `path` as `alias`.
+    pub fn attach(mut self, alias: &str, path: &str) -> Self {
+        self.attached.push(Attachment {
+            alias: alias.to_string(),
+            path: path.to_string(),
+            read_only: false,
+        });
+        self
+    }
+
+    /// Attach the database at `path` as `alias`, read-only.
+    pub fn attach_read_only(mut self, alias: &str, path: &str) -> Self {
+        self.attached.push(Attachment {
+            alias: alias.to_string(),
+            path: path.to_string(),
+            read_only: true,
+        });
+        self
+    }
+}
+
+/// A `PRAGMA journal_mode` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    Wal,
+    Off,
+}
+
+impl fmt::Display for JournalMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            JournalMode::Delete => "DELETE",
+            JournalMode::Truncate => "TRUNCATE",
+            JournalMode::Persist => "PERSIST",
+            JournalMode::Memory => "MEMORY",
+            JournalMode::Wal => "WAL",
+            JournalMode::Off => "OFF",
+        })
+    }
+}
+
+/// A `PRAGMA synchronous` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Synchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl fmt::Display for Synchronous {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Synchronous::Off => "OFF",
+            Synchronous::Normal => "NORMAL",
+            Synchronous::Full => "FULL",
+            Synchronous::Extra => "EXTRA",
+        })
+    }
+}
+
+/// A `PRAGMA locking_mode` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockingMode {
+    Normal,
+    Exclusive,
+}
+
+impl fmt::Display for LockingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LockingMode::Normal => "NORMAL",
+            LockingMode::Exclusive => "EXCLUSIVE",
+        })
+    }
+}
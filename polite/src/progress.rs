@@ -0,0 +1,84 @@
+//! Progress reporting for long reads and writes.
+//!
+//! Attach a [`ProgressObserver`] through [`ReadOptions::progress`](crate::ReadOptions) or
+//! [`WriteOptions::progress`](crate::WriteOptions) to be told which phase a read is in and how
+//! many rows have been fetched or written so far, e.g. to drive a progress bar. When no
+//! observer is attached nothing is reported.
+//!
+//! ```rust
+//! use polite::progress::{Phase, ProgressObserver};
+//!
+//! #[derive(Debug)]
+//! struct Log;
+//!
+//! impl ProgressObserver for Log {
+//!     fn phase(&self, phase: Phase) {
+//!         eprintln!("{phase}...");
+//!     }
+//!     fn rows_fetched(&self, partition: usize, rows: usize, total: Option<usize>) {
+//!         eprintln!("partition {partition}: {rows}/{total:?} rows");
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+
+/// Rows fetched or written between progress callbacks.
+pub const PROGRESS_CHUNK: usize = 1024;
+
+/// A stage of a read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Opening the database and checking the query.
+    Preflight,
+    /// Working out the result's column names and types.
+    Metadata,
+    /// Counting the rows each partition will return, for an observer whose
+    /// [`ProgressObserver::wants_totals`] is true.
+    Counting,
+    /// Fetching rows into Arrow arrays.
+    Fetching,
+    /// Converting the Arrow arrays into a DataFrame.
+    Converting,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Phase::Preflight => "preflight",
+            Phase::Metadata => "metadata",
+            Phase::Counting => "counting",
+            Phase::Fetching => "fetching",
+            Phase::Converting => "converting",
+        })
+    }
+}
+
+/// Callbacks reporting the progress of a read or write. Every method does nothing by default,
+/// so implement only those you need.
+///
+/// Row counts are reported every [`PROGRESS_CHUNK`] rows and once more at the end. Partitions
+/// are fetched in parallel, so `rows_fetched` may be called from several threads at once.
+pub trait ProgressObserver: Send + Sync {
+    /// A read entered `phase`.
+    fn phase(&self, _phase: Phase) {}
+
+    /// `partition` has fetched `rows` rows so far, out of `total` when it is known.
+    fn rows_fetched(&self, _partition: usize, _rows: usize, _total: Option<usize>) {}
+
+    /// Whether `rows_fetched` should be given each partition's total. Finding it runs every
+    /// partition's query once more as a `COUNT(*)` before fetching, so it is off by default
+    /// and `total` is `None`.
+    fn wants_totals(&self) -> bool {
+        false
+    }
+
+    /// `rows` of the `total` rows have been written to `table` so far.
+    fn rows_written(&self, _table: &str, _rows: usize, _total: usize) {}
+}
+
+impl fmt::Debug for dyn ProgressObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressObserver")
+    }
+}
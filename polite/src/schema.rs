@@ -0,0 +1,249 @@
+//! Catalog introspection without writing PRAGMA SQL by hand.
+//!
+//! Each function runs against an open connection and returns a DataFrame with one row per
+//! table, column, index or foreign key, so it can be filtered and joined like any other.
+//! Tables in every schema on the connection are listed, including `temp` and any attached
+//! databases; per-table functions look `table` up as SQLite would resolve it in a query.
+
+use crate::db::quote_ident;
+use crate::options::ReadOptions;
+use crate::types::decl_dtype;
+use crate::PoliteError;
+use polars::prelude::*;
+use rusqlite::{Connection, Params, Row};
+
+fn frame_err(e: PolarsError) -> PoliteError {
+    PoliteError::DataFrame { source: e }
+}
+
+/// Collect the rows of `sql` with `f`.
+fn query_rows<T, P: Params>(
+    conn: &Connection,
+    sql: &str,
+    params: P,
+    f: impl FnMut(&Row<'_>) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, PoliteError> {
+    conn.prepare(sql)
+        .and_then(|mut stmt| stmt.query_map(params, f)?.collect())
+        .map_err(|e| PoliteError::Exec {
+            sql: sql.to_string(),
+            source: e,
+        })
+}
+
+/// A row of `pragma_table_list`: `(schema, name, type, ncol, strict)`.
+type TableEntry = (String, String, String, i64, bool);
+
+/// A row of `pragma_foreign_key_list`: `(id, seq, from, table, to, on_update, on_delete)`.
+type ForeignKeyEntry = (i64, i64, String, String, Option<String>, String, String);
+
+/// Tables and views in every schema, of the given types.
+fn table_list(conn: &Connection, types: &[&str]) -> Result<Vec<TableEntry>, PoliteError> {
+    let rows: Vec<TableEntry> = query_rows(
+        conn,
+        "SELECT schema, name, type, ncol, strict FROM pragma_table_list
+         WHERE name NOT LIKE 'sqlite\\_%' ESCAPE '\\' ORDER BY schema, name",
+        [],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
+    )?;
+    Ok(rows
+        .into_iter()
+        .filter(|(_, _, ty, _, _)| types.contains(&ty.as_str()))
+        .collect())
+}
+
+/// The schema holding `table`, or [`PoliteError::NoSuchTable`].
+fn resolve(conn: &Connection, table: &str) -> Result<String, PoliteError> {
+    // `temp` first, then `main` and attached databases in the order they were attached, as a
+    // query searches them
+    let schemas = query_rows(
+        conn,
+        "SELECT t.schema FROM pragma_table_list(?1) t JOIN pragma_database_list d
+         ON d.name = t.schema ORDER BY t.schema <> 'temp', d.seq",
+        [table],
+        |r| r.get(0),
+    )?;
+    schemas
+        .into_iter()
+        .next()
+        .ok_or_else(|| PoliteError::NoSuchTable {
+            table: table.to_string(),
+        })
+}
+
+/// The tables on the connection, including virtual tables, as `schema`, `name`, `type`
+/// (`table` or `virtual`), `ncol` and `strict`. SQLite's own tables are left out.
+pub fn list_tables(conn: &Connection) -> Result<DataFrame, PoliteError> {
+    tables_frame(table_list(conn, &["table", "virtual"])?)
+}
+
+/// The views on the connection, as `schema`, `name`, `type` (`view`), `ncol` and `strict`.
+pub fn list_views(conn: &Connection) -> Result<DataFrame, PoliteError> {
+    tables_frame(table_list(conn, &["view"])?)
+}
+
+fn tables_frame(rows: Vec<TableEntry>) -> Result<DataFrame, PoliteError> {
+    df! {
+        "schema" => rows.iter().map(|r| r.0.as_str()).collect::<Vec<_>>(),
+        "name" => rows.iter().map(|r| r.1.as_str()).collect::<Vec<_>>(),
+        "type" => rows.iter().map(|r| r.2.as_str()).collect::<Vec<_>>(),
+        "ncol" => rows.iter().map(|r| r.3).collect::<Vec<_>>(),
+        "strict" => rows.iter().map(|r| r.4).collect::<Vec<_>>(),
+    }
+    .map_err(frame_err)
+}
+
+/// The columns of a table or view, as `name`, `decl_type`, `dtype` (the Polars dtype an empty
+/// read of the column has), `nullable`, `default` (as SQL text) and `pk` (position in the
+/// primary key from 1, or 0 if not part of it).
+pub fn table_schema(conn: &Connection, table: &str) -> Result<DataFrame, PoliteError> {
+    table_schema_with_options(conn, table, &ReadOptions::default())
+}
+
+/// Like [`table_schema`], mapping dtypes as a read with `options` would, e.g. with
+/// [`ReadOptions::widen_integers`].
+pub fn table_schema_with_options(
+    conn: &Connection,
+    table: &str,
+    options: &ReadOptions,
+) -> Result<DataFrame, PoliteError> {
+    let schema = resolve(conn, table)?;
+    let rows: Vec<(String, String, bool, Option<String>, i64)> = query_rows(
+        conn,
+        "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1, ?2) ORDER BY cid",
+        [table, schema.as_str()],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
+    )?;
+    let dtypes: Vec<String> = rows
+        .iter()
+        .map(|(_, decl, ..)| {
+            let decl = Some(decl.as_str()).filter(|d| !d.is_empty());
+            decl_dtype(decl, options).to_string()
+        })
+        .collect();
+    df! {
+        "name" => rows.iter().map(|r| r.0.as_str()).collect::<Vec<_>>(),
+        "decl_type" => rows
+            .iter()
+            .map(|r| Some(r.1.as_str()).filter(|d| !d.is_empty()))
+            .collect::<Vec<_>>(),
+        "dtype" => dtypes,
+        "nullable" => rows.iter().map(|r| !r.2).collect::<Vec<_>>(),
+        "default" => rows.iter().map(|r| r.3.as_deref()).collect::<Vec<_>>(),
+        "pk" => rows.iter().map(|r| r.4).collect::<Vec<_>>(),
+    }
+    .map_err(frame_err)
+}
+
+/// The indexes on a table, as `name`, `unique`, `origin` (`c` for `CREATE INDEX`, `u` for a
+/// `UNIQUE` constraint, `pk` for the primary key), `partial` and `columns` (a list of the
+/// indexed column names, with null for an expression).
+pub fn indexes(conn: &Connection, table: &str) -> Result<DataFrame, PoliteError> {
+    let schema = resolve(conn, table)?;
+    let rows: Vec<(String, bool, String, bool)> = query_rows(
+        conn,
+        "SELECT name, \"unique\", origin, partial FROM pragma_index_list(?1, ?2) ORDER BY name",
+        [table, schema.as_str()],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+    )?;
+    let columns = rows
+        .iter()
+        .map(|(name, ..)| {
+            let names: Vec<Option<String>> = query_rows(
+                conn,
+                "SELECT name FROM pragma_index_info(?1, ?2) ORDER BY seqno",
+                [name.as_str(), schema.as_str()],
+                |r| r.get(0),
+            )?;
+            Ok(Series::new("".into(), names))
+        })
+        .collect::<Result<Vec<_>, PoliteError>>()?;
+    let columns = Series::new("columns".into(), columns)
+        .cast(&DataType::List(Box::new(DataType::String)))
+        .map_err(frame_err)?;
+    let mut df = df! {
+        "name" => rows.iter().map(|r| r.0.as_str()).collect::<Vec<_>>(),
+        "unique" => rows.iter().map(|r| r.1).collect::<Vec<_>>(),
+        "origin" => rows.iter().map(|r| r.2.as_str()).collect::<Vec<_>>(),
+        "partial" => rows.iter().map(|r| r.3).collect::<Vec<_>>(),
+    }
+    .map_err(frame_err)?;
+    df.with_column(columns).map_err(frame_err)?;
+    Ok(df)
+}
+
+/// The foreign keys of a table, one row per referencing column, as `id` (shared by the
+/// columns of a composite key), `seq`, `from`, `table` (the referenced table), `to` (null
+/// when referencing its primary key), `on_update` and `on_delete`.
+pub fn foreign_keys(conn: &Connection, table: &str) -> Result<DataFrame, PoliteError> {
+    let schema = resolve(conn, table)?;
+    let rows: Vec<ForeignKeyEntry> = query_rows(
+        conn,
+        "SELECT id, seq, \"from\", \"table\", \"to\", on_update, on_delete
+         FROM pragma_foreign_key_list(?1, ?2) ORDER BY id, seq",
+        [table, schema.as_str()],
+        |r| {
+            Ok((
+                r.get(0)?,
+                r.get(1)?,
+                r.get(2)?,
+                r.get(3)?,
+                r.get(4)?,
+                r.get(5)?,
+                r.get(6)?,
+            ))
+        },
+    )?;
+    df! {
+        "id" => rows.iter().map(|r| r.0).collect::<Vec<_>>(),
+        "seq" => rows.iter().map(|r| r.1).collect::<Vec<_>>(),
+        "from" => rows.iter().map(|r| r.2.as_str()).collect::<Vec<_>>(),
+        "table" => rows.iter().map(|r| r.3.as_str()).collect::<Vec<_>>(),
+        "to" => rows.iter().map(|r| r.4.as_deref()).collect::<Vec<_>>(),
+        "on_update" => rows.iter().map(|r| r.5.as_str()).collect::<Vec<_>>(),
+        "on_delete" => rows.iter().map(|r| r.6.as_str()).collect::<Vec<_>>(),
+    }
+    .map_err(frame_err)
+}
+
+/// The size of each ordinary table, as `schema`, `name`, `rows`, and `pages` and `bytes`
+/// (the b-tree pages the table and its indexes use, and their total size). Pages and bytes
+/// are null when SQLite was built without the `dbstat` virtual table.
+pub fn table_stats(conn: &Connection) -> Result<DataFrame, PoliteError> {
+    let tables = table_list(conn, &["table"])?;
+    let mut rows = Vec::with_capacity(tables.len());
+    let mut pages = Vec::with_capacity(tables.len());
+    let mut bytes = Vec::with_capacity(tables.len());
+    for (schema, name, ..) in &tables {
+        let sql = format!(
+            "SELECT count(*) FROM {}.{}",
+            quote_ident(schema),
+            quote_ident(name)
+        );
+        rows.push(conn.query_row(&sql, [], |r| r.get::<_, i64>(0))?);
+        // Indexes are stored in b-trees of their own, named after the index
+        let usage = conn.query_row(
+            "SELECT count(*), sum(pgsize) FROM dbstat(?1)
+             WHERE name = ?2 OR name IN (SELECT name FROM pragma_index_list(?2, ?1))",
+            [schema.as_str(), name.as_str()],
+            |r| Ok((r.get::<_, i64>(0)?, r.get::<_, Option<i64>>(1)?)),
+        );
+        let (page_count, size) = match usage {
+            Ok((count, size)) => (Some(count), Some(size.unwrap_or(0))),
+            Err(rusqlite::Error::SqliteFailure(_, Some(msg))) if msg.contains("dbstat") => {
+                (None, None)
+            }
+            Err(e) => return Err(PoliteError::from(e)),
+        };
+        pages.push(page_count);
+        bytes.push(size);
+    }
+    df! {
+        "schema" => tables.iter().map(|t| t.0.as_str()).collect::<Vec<_>>(),
+        "name" => tables.iter().map(|t| t.1.as_str()).collect::<Vec<_>>(),
+        "rows" => rows,
+        "pages" => pages,
+        "bytes" => bytes,
+    }
+    .map_err(frame_err)
+}
@@ -0,0 +1,77 @@
+//! Whole databases as bytes, and databases that live only in memory.
+//!
+//! [`serialize_db`] copies a database into a byte vector, e.g. to send it to another service,
+//! and [`deserialize_db`] opens bytes as an in-memory database. A private `:memory:` database
+//! can't be reached by the path-based functions, which open their own connections, so
+//! [`memory_db_path`] names an in-memory database that every connection in the process opening
+//! that path shares. Pass it to [`save_dataframe`](crate::save_dataframe),
+//! [`to_dataframe`](crate::to_dataframe) and the like while a connection to it stays open.
+//!
+//! ```rust
+//! use polite::serialize::{deserialize_db_as, memory_db_path, serialize_db};
+//! use polite::{connect_sqlite, save_dataframe, to_dataframe};
+//! use polars::prelude::*;
+//!
+//! let path = memory_db_path("fixture");
+//! let conn = connect_sqlite(Some(&path)).unwrap();
+//! let df = df! { "id" => [1i64, 2], "name" => ["Alice", "Bob"] }.unwrap();
+//! save_dataframe(&path, "users", &df).unwrap();
+//! let bytes = serialize_db(&conn).unwrap();
+//!
+//! let _copy = deserialize_db_as("copy", &bytes).unwrap();
+//! let users = to_dataframe(&memory_db_path("copy"), "SELECT * FROM users").unwrap();
+//! assert!(users.equals(&df));
+//! ```
+
+use crate::db::{connect_sqlite, uri_path_escape};
+use crate::PoliteError;
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, MAIN_DB};
+
+/// A path naming an in-memory database shared by every connection in this process that opens
+/// it, including the ones reads open. Its contents are dropped once no connection to it is
+/// left open. Any name can be used, as it is escaped in the path.
+pub fn memory_db_path(name: &str) -> String {
+    format!("file:/{}?vfs=memdb", uri_path_escape(name))
+}
+
+/// The contents of the `main` database of `conn`, as they would be written to a file.
+pub fn serialize_db(conn: &Connection) -> Result<Vec<u8>, PoliteError> {
+    conn.serialize(MAIN_DB)
+        .map(|data| data.to_vec())
+        .map_err(PoliteError::from)
+}
+
+/// Open a copy of a serialized database as a private in-memory database, which can be
+/// written to and grows as needed.
+pub fn deserialize_db(bytes: &[u8]) -> Result<Connection, PoliteError> {
+    let mut conn = connect_sqlite(None)?;
+    let bytes = rollback_journal(bytes);
+    conn.deserialize_read_exact(MAIN_DB, bytes.as_slice(), bytes.len(), false)?;
+    Ok(conn)
+}
+
+/// Open a copy of a serialized database as the shared in-memory database at
+/// [`memory_db_path`]`(name)`, replacing what it held. Keep the connection open for as long
+/// as the database is needed.
+pub fn deserialize_db_as(name: &str, bytes: &[u8]) -> Result<Connection, PoliteError> {
+    let source = deserialize_db(bytes)?;
+    let mut conn = connect_sqlite(Some(&memory_db_path(name)))?;
+    // A deserialized database is private to its connection, so its pages are copied over
+    // rather than deserialized in place
+    Backup::new(&source, &mut conn)
+        .and_then(|backup| backup.run_to_completion(256, std::time::Duration::ZERO, None))?;
+    Ok(conn)
+}
+
+/// `bytes` with the header set to the rollback journal, since an in-memory database can't
+/// open a write-ahead log. Serializing a database in WAL mode records it in the header.
+fn rollback_journal(bytes: &[u8]) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    // The file format read and write versions, 2 for WAL and 1 for legacy
+    if bytes.len() >= 100 && bytes[18] == 2 && bytes[19] == 2 {
+        bytes[18] = 1;
+        bytes[19] = 1;
+    }
+    bytes
+}
@@ -0,0 +1,281 @@
+//! Capturing the changes made to a database and applying them to another.
+//!
+//! Built on SQLite's session extension, enabled with the `session` feature. A
+//! [`ChangeCapture`] records every insert, update and delete made through its connection to the
+//! tables it is attached to, including writes made with
+//! [`from_dataframe`](crate::from_dataframe), and turns them into a [`Changeset`]: bytes that
+//! can be stored or sent elsewhere, listed as a DataFrame, and applied to another database.
+//!
+//! Only tables with a `PRIMARY KEY` are recorded, since a change is identified by its key. Give
+//! the tables you write one with [`WriteOptions::primary_key`](crate::WriteOptions).
+//!
+//! ```rust
+//! use polite::session::{ChangeCapture, OnConflict};
+//! use polite::{connect_sqlite, from_dataframe_with_options, WriteOptions};
+//! use polars::prelude::*;
+//!
+//! let source = connect_sqlite(None).unwrap();
+//! let replica = connect_sqlite(None).unwrap();
+//! let options = WriteOptions {
+//!     primary_key: vec!["id".into()],
+//!     ..Default::default()
+//! };
+//! let empty = df! { "id" => Vec::<i64>::new(), "name" => Vec::<String>::new() }.unwrap();
+//! from_dataframe_with_options(&replica, "users", &empty, &options).unwrap();
+//!
+//! let mut capture = ChangeCapture::new(&source).unwrap();
+//! capture.attach_all().unwrap();
+//! let users = df! { "id" => [1i64, 2], "name" => ["Alice", "Bob"] }.unwrap();
+//! from_dataframe_with_options(&source, "users", &users, &options).unwrap();
+//!
+//! let changeset = capture.changeset().unwrap();
+//! assert_eq!(capture.changes().unwrap().height(), 2);
+//! changeset.apply(&replica, OnConflict::Abort).unwrap();
+//! ```
+
+use crate::blob::to_hex;
+use crate::PoliteError;
+use fallible_streaming_iterator::FallibleStreamingIterator;
+use polars::prelude::*;
+use rusqlite::hooks::Action;
+use rusqlite::session::{ChangesetItem, ChangesetIter, ConflictAction, ConflictType, Session};
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use serde_json::{Map, Value as Json};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+
+/// Records the changes made through a connection to the tables it is attached to, from the
+/// moment it is attached until it is dropped.
+pub struct ChangeCapture<'conn> {
+    conn: &'conn Connection,
+    session: Session<'conn>,
+}
+
+impl<'conn> ChangeCapture<'conn> {
+    /// Start a capture on the `main` database of `conn`, attached to no table yet.
+    pub fn new(conn: &'conn Connection) -> Result<Self, PoliteError> {
+        let session = Session::new(conn)?;
+        Ok(ChangeCapture { conn, session })
+    }
+
+    /// Record the changes made to `table`, which need not exist yet.
+    pub fn attach(&mut self, table: &str) -> Result<(), PoliteError> {
+        self.session.attach(Some(table)).map_err(PoliteError::from)
+    }
+
+    /// Record the changes made to every table, including tables created later.
+    pub fn attach_all(&mut self) -> Result<(), PoliteError> {
+        self.session.attach(None::<&str>).map_err(PoliteError::from)
+    }
+
+    /// Whether no change has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.session.is_empty()
+    }
+
+    /// The changes recorded so far, with each row's net change: a row inserted then updated is
+    /// a single insert of its final values.
+    pub fn changeset(&mut self) -> Result<Changeset, PoliteError> {
+        let mut bytes = vec![];
+        self.session.changeset_strm(&mut bytes)?;
+        Ok(Changeset { bytes })
+    }
+
+    /// The changes recorded so far as a DataFrame, as [`Changeset::to_dataframe`] lists them.
+    pub fn changes(&mut self) -> Result<DataFrame, PoliteError> {
+        self.changeset()?.to_dataframe(self.conn)
+    }
+}
+
+/// What to do when a change can't be applied as recorded: the row to update or delete is
+/// missing or differs, the row to insert already exists, or a constraint fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnConflict {
+    /// Roll back every change in the changeset and return
+    /// [`PoliteError::ChangesetConflict`].
+    #[default]
+    Abort,
+    /// Skip the conflicting change and apply the rest.
+    Omit,
+    /// Overwrite a row that exists with different values with the recorded one. Changes to a
+    /// missing row are skipped, and a failed constraint aborts as with `Abort`.
+    Replace,
+}
+
+/// A set of changes recorded by a [`ChangeCapture`], in SQLite's changeset format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changeset {
+    bytes: Vec<u8>,
+}
+
+impl Changeset {
+    /// A changeset received as bytes, e.g. from [`Changeset::as_bytes`] on another machine.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Changeset { bytes }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// One row per change, as `op` (`INSERT`, `UPDATE` or `DELETE`), `table`, `pk` (the
+    /// changed row's primary key) and `old` and `new` (the values before and after, `null`
+    /// for an insert's `old` and a delete's `new`). `pk`, `old` and `new` are JSON objects
+    /// keyed by column name, where an update lists only the columns it changed. Blobs are
+    /// given as hex.
+    ///
+    /// Column names are looked up on `conn`, so pass the database the changes were made to
+    /// or will be applied to. Columns of tables it doesn't have are keyed by position.
+    pub fn to_dataframe(&self, conn: &Connection) -> Result<DataFrame, PoliteError> {
+        let (mut ops, mut tables, mut pks, mut olds, mut news) =
+            (vec![], vec![], vec![], vec![], vec![]);
+        let mut input: &[u8] = &self.bytes;
+        let input: &mut dyn Read = &mut input;
+        let mut changes = ChangesetIter::start_strm(&input)?;
+        while let Some(item) = changes.next()? {
+            let op = item.op()?;
+            let names = column_names(conn, op.table_name(), op.number_of_columns() as usize)?;
+            let pk = item.pk()?;
+            let old = |col| item.old_value(col);
+            let new = |col| item.new_value(col);
+            let (name, key, before, after) = match op.code() {
+                Action::SQLITE_INSERT => (
+                    "INSERT",
+                    row(&names, pk, true, new),
+                    None,
+                    Some(row(&names, pk, false, new)),
+                ),
+                Action::SQLITE_DELETE => (
+                    "DELETE",
+                    row(&names, pk, true, old),
+                    Some(row(&names, pk, false, old)),
+                    None,
+                ),
+                _ => (
+                    "UPDATE",
+                    row(&names, pk, true, old),
+                    Some(row(&names, pk, false, old)),
+                    Some(row(&names, pk, false, new)),
+                ),
+            };
+            ops.push(name);
+            tables.push(op.table_name().to_string());
+            pks.push(key.to_string());
+            olds.push(before.map(|v| v.to_string()));
+            news.push(after.map(|v| v.to_string()));
+        }
+        df! {
+            "op" => ops,
+            "table" => tables,
+            "pk" => pks,
+            "old" => olds,
+            "new" => news,
+        }
+        .map_err(|e| PoliteError::DataFrame { source: e })
+    }
+
+    /// Apply the changes to `conn` in one transaction, handling conflicts as `on_conflict`
+    /// says.
+    pub fn apply(&self, conn: &Connection, on_conflict: OnConflict) -> Result<(), PoliteError> {
+        let aborted_on = Arc::new(Mutex::new(None));
+        let seen = Arc::clone(&aborted_on);
+        let handler = move |conflict: ConflictType, item: ChangesetItem| {
+            let action = match (on_conflict, &conflict) {
+                (OnConflict::Omit, _) => ConflictAction::SQLITE_CHANGESET_OMIT,
+                (
+                    OnConflict::Replace,
+                    ConflictType::SQLITE_CHANGESET_DATA | ConflictType::SQLITE_CHANGESET_CONFLICT,
+                ) => ConflictAction::SQLITE_CHANGESET_REPLACE,
+                (OnConflict::Replace, ConflictType::SQLITE_CHANGESET_NOTFOUND) => {
+                    ConflictAction::SQLITE_CHANGESET_OMIT
+                }
+                _ => ConflictAction::SQLITE_CHANGESET_ABORT,
+            };
+            if action == ConflictAction::SQLITE_CHANGESET_ABORT {
+                let table = item
+                    .op()
+                    .map(|op| op.table_name().to_string())
+                    .unwrap_or_default();
+                seen.lock()
+                    .unwrap()
+                    .get_or_insert((table, conflict_name(&conflict)));
+            }
+            action
+        };
+        let mut input: &[u8] = &self.bytes;
+        conn.apply_strm(&mut input, None::<fn(&str) -> bool>, handler)
+            .map_err(|e| match aborted_on.lock().unwrap().take() {
+                Some((table, conflict)) => PoliteError::ChangesetConflict { table, conflict },
+                None => PoliteError::from(e),
+            })
+    }
+}
+
+/// The names of the columns of `table`, or their positions if `conn` has no such table.
+fn column_names(conn: &Connection, table: &str, count: usize) -> Result<Vec<String>, PoliteError> {
+    let sql = "SELECT name FROM pragma_table_info(?1) ORDER BY cid";
+    let names: Vec<String> = conn
+        .prepare(sql)
+        .and_then(|mut stmt| stmt.query_map([table], |r| r.get(0))?.collect())
+        .map_err(|e| PoliteError::Exec {
+            sql: sql.to_string(),
+            source: e,
+        })?;
+    if names.len() == count {
+        Ok(names)
+    } else {
+        Ok((0..count).map(|i| i.to_string()).collect())
+    }
+}
+
+/// The primary key columns (`key`) or other columns of a change as a JSON object, leaving out
+/// those the change doesn't record.
+fn row<'a>(
+    names: &[String],
+    pk: &[u8],
+    key: bool,
+    value: impl Fn(usize) -> rusqlite::Result<ValueRef<'a>>,
+) -> Json {
+    let mut object = Map::new();
+    for (col, name) in names.iter().enumerate() {
+        if (pk.get(col).copied().unwrap_or(0) != 0) != key {
+            continue;
+        }
+        // An update leaves the columns it didn't change unset
+        if let Ok(v) = value(col) {
+            object.insert(name.clone(), to_json(v));
+        }
+    }
+    Json::Object(object)
+}
+
+fn to_json(value: ValueRef<'_>) -> Json {
+    match value {
+        ValueRef::Null => Json::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(s) => String::from_utf8_lossy(s).into(),
+        ValueRef::Blob(b) => to_hex(b).into(),
+    }
+}
+
+fn conflict_name(conflict: &ConflictType) -> String {
+    match conflict {
+        ConflictType::SQLITE_CHANGESET_DATA => "row differs",
+        ConflictType::SQLITE_CHANGESET_NOTFOUND => "row not found",
+        ConflictType::SQLITE_CHANGESET_CONFLICT => "row already exists",
+        ConflictType::SQLITE_CHANGESET_CONSTRAINT => "constraint failed",
+        ConflictType::SQLITE_CHANGESET_FOREIGN_KEY => "foreign key violated",
+        _ => "unknown conflict",
+    }
+    .to_string()
+}
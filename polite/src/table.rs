@@ -0,0 +1,415 @@
+//! Reading a table without writing SQL by hand.
+//!
+//! [`read_table`] builds the query for "these columns of that table where X, ordered by Y" with
+//! sqlparser, so identifiers are always quoted, and filter values never appear in its text.
+//!
+//! A read hands ConnectorX only the query's text, which it rewrites (to probe the types, count
+//! rows and split partitions) and runs again on each of its connections, so the values can't
+//! be bound as `?` parameters. Instead each one is written as a call to `polite_param(i)`, a
+//! deterministic function created on every connection the read opens that returns the `i`th
+//! value. SQLite evaluates it once per statement, and can still use an index on the column.
+//!
+//! ```rust
+//! use polite::table::{read_table, Filter, OrderBy, ReadTableOptions};
+//! # let tmp = tempfile::NamedTempFile::new().unwrap();
+//! # let db_path = tmp.path().to_str().unwrap();
+//! # let conn = polite::connect_sqlite(Some(db_path)).unwrap();
+//! # conn.execute_batch("CREATE TABLE users (id INTEGER, name TEXT, age INTEGER)").unwrap();
+//!
+//! let adults = read_table(
+//!     db_path,
+//!     "users",
+//!     &ReadTableOptions {
+//!         columns: vec!["id".into(), "name".into()],
+//!         filter: vec![Filter::gt_eq("age", 18)],
+//!         order_by: vec![OrderBy::desc("age")],
+//!         limit: Some(10),
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//! ```
+
+use crate::dataframe::to_dataframe_with_options;
+use crate::functions::FunctionFlags;
+use crate::options::ReadOptions;
+use crate::PoliteError;
+use polars::prelude::DataFrame;
+use rusqlite::types::Value;
+use sqlparser::ast::{
+    BinaryOperator, Expr, Function, FunctionArg, FunctionArgExpr, Ident, ObjectName, Offset,
+    OffsetRows, OrderByExpr, Query, Select, SelectItem, SetExpr, Statement, TableFactor,
+    TableWithJoins, Value as SqlValue, WildcardAdditionalOptions,
+};
+
+/// Name of the function [`Sample`] orders rows by, created on the read's connections.
+const SAMPLE_KEY_FN: &str = "polite_sample_key";
+
+/// Name of the function filter values are read from, created on the read's connections.
+const PARAM_FN: &str = "polite_param";
+
+/// What [`read_table`] reads from the table.
+#[derive(Debug, Clone, Default)]
+pub struct ReadTableOptions {
+    /// Columns to read, in order. Every column if empty.
+    pub columns: Vec<String>,
+    /// Conditions every row read must meet.
+    pub filter: Vec<Filter>,
+    pub order_by: Vec<OrderBy>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// Read a random sample of the rows meeting `filter`, which are then ordered and limited.
+    pub sample: Option<Sample>,
+    /// How the query is read.
+    pub read: ReadOptions,
+}
+
+/// A condition on one column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Compare {
+        column: String,
+        op: CompareOp,
+        value: Value,
+    },
+    In {
+        column: String,
+        values: Vec<Value>,
+    },
+    IsNull(String),
+    IsNotNull(String),
+}
+
+/// A comparison between a column and a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    /// SQL `LIKE`, with `%` and `_` wildcards
+    Like,
+}
+
+impl Filter {
+    fn compare(column: &str, op: CompareOp, value: impl Into<Value>) -> Self {
+        Filter::Compare {
+            column: column.to_string(),
+            op,
+            value: value.into(),
+        }
+    }
+
+    pub fn eq(column: &str, value: impl Into<Value>) -> Self {
+        Self::compare(column, CompareOp::Eq, value)
+    }
+
+    pub fn not_eq(column: &str, value: impl Into<Value>) -> Self {
+        Self::compare(column, CompareOp::NotEq, value)
+    }
+
+    pub fn lt(column: &str, value: impl Into<Value>) -> Self {
+        Self::compare(column, CompareOp::Lt, value)
+    }
+
+    pub fn lt_eq(column: &str, value: impl Into<Value>) -> Self {
+        Self::compare(column, CompareOp::LtEq, value)
+    }
+
+    pub fn gt(column: &str, value: impl Into<Value>) -> Self {
+        Self::compare(column, CompareOp::Gt, value)
+    }
+
+    pub fn gt_eq(column: &str, value: impl Into<Value>) -> Self {
+        Self::compare(column, CompareOp::GtEq, value)
+    }
+
+    pub fn like(column: &str, pattern: &str) -> Self {
+        Self::compare(column, CompareOp::Like, pattern.to_string())
+    }
+
+    pub fn is_in<V: Into<Value>>(column: &str, values: impl IntoIterator<Item = V>) -> Self {
+        Filter::In {
+            column: column.to_string(),
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn is_null(column: &str) -> Self {
+        Filter::IsNull(column.to_string())
+    }
+
+    pub fn is_not_null(column: &str) -> Self {
+        Filter::IsNotNull(column.to_string())
+    }
+
+    /// The condition, with its values added to `params`.
+    fn to_expr(&self, params: &mut Vec<Value>) -> Expr {
+        match self {
+            Filter::Compare { column, op, value } => {
+                let (left, right) = (
+                    Box::new(column_expr(column)),
+                    Box::new(param(value, params)),
+                );
+                let op = match op {
+                    CompareOp::Like => {
+                        return Expr::Like {
+                            negated: false,
+                            expr: left,
+                            pattern: right,
+                            escape_char: None,
+                        }
+                    }
+                    CompareOp::Eq => BinaryOperator::Eq,
+                    CompareOp::NotEq => BinaryOperator::NotEq,
+                    CompareOp::Lt => BinaryOperator::Lt,
+                    CompareOp::LtEq => BinaryOperator::LtEq,
+                    CompareOp::Gt => BinaryOperator::Gt,
+                    CompareOp::GtEq => BinaryOperator::GtEq,
+                };
+                Expr::BinaryOp { left, op, right }
+            }
+            Filter::In { column, values } => Expr::InList {
+                expr: Box::new(column_expr(column)),
+                list: values.iter().map(|value| param(value, params)).collect(),
+                negated: false,
+            },
+            Filter::IsNull(column) => Expr::IsNull(Box::new(column_expr(column))),
+            Filter::IsNotNull(column) => Expr::IsNotNull(Box::new(column_expr(column))),
+        }
+    }
+}
+
+/// A column to order by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderBy {
+    pub column: String,
+    pub descending: bool,
+}
+
+impl OrderBy {
+    pub fn asc(column: &str) -> Self {
+        OrderBy {
+            column: column.to_string(),
+            descending: false,
+        }
+    }
+
+    pub fn desc(column: &str) -> Self {
+        OrderBy {
+            column: column.to_string(),
+            descending: true,
+        }
+    }
+}
+
+/// A reproducible random sample of a table's rows, picked by a hash of their rowid. The same
+/// seed picks the same rows while the table is unchanged. Tables declared `WITHOUT ROWID` can't
+/// be sampled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// Number of rows to pick, or all of them if there are fewer.
+    pub rows: usize,
+    pub seed: u64,
+}
+
+/// Read the rows of `table` chosen by `options` into a DataFrame.
+pub fn read_table(
+    db_path: &str,
+    table: &str,
+    options: &ReadTableOptions,
+) -> Result<DataFrame, PoliteError> {
+    let (sql, params) = table_query(table, options);
+    let mut read = options.read.clone();
+    if !params.is_empty() {
+        let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+        read.connect.functions = read
+            .connect
+            .functions
+            .scalar(PARAM_FN, 1, flags, move |ctx| {
+                let i: usize = ctx.get(0)?;
+                params.get(i).cloned().ok_or_else(|| {
+                    rusqlite::Error::UserFunctionError(format!("no filter value {i}").into())
+                })
+            });
+    }
+    if options.sample.is_some() {
+        let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+        read.connect.functions = read
+            .connect
+            .functions
+            .scalar(SAMPLE_KEY_FN, 2, flags, |ctx| {
+                Ok(sample_key(ctx.get(0)?, ctx.get(1)?))
+            });
+    }
+    to_dataframe_with_options(db_path, &sql, &read)
+}
+
+/// The query [`read_table`] runs, and the filter values its `polite_param(i)` calls return.
+pub fn table_query(table: &str, options: &ReadTableOptions) -> (String, Vec<Value>) {
+    let mut params = Vec::new();
+    let projection = if options.columns.is_empty() {
+        vec![SelectItem::Wildcard(WildcardAdditionalOptions::default())]
+    } else {
+        options
+            .columns
+            .iter()
+            .map(|c| SelectItem::UnnamedExpr(column_expr(c)))
+            .collect()
+    };
+    let mut selection = conjunction(options.filter.iter().map(|f| f.to_expr(&mut params)));
+
+    // The sample is drawn from the filtered rows, and the outer query then orders and limits it
+    if let Some(sample) = options.sample {
+        let rowid = Expr::Identifier(Ident::new("rowid"));
+        let key = call(SAMPLE_KEY_FN, [rowid.clone(), number(sample.seed as i64)]);
+        let sampled = query(
+            vec![SelectItem::UnnamedExpr(rowid.clone())],
+            table,
+            selection,
+            vec![OrderByExpr {
+                expr: key,
+                asc: None,
+                nulls_first: None,
+            }],
+            Some(sample.rows),
+            None,
+        );
+        selection = Some(Expr::InSubquery {
+            expr: Box::new(rowid),
+            subquery: Box::new(sampled),
+            negated: false,
+        });
+    }
+
+    let order_by = options
+        .order_by
+        .iter()
+        .map(|o| OrderByExpr {
+            expr: column_expr(&o.column),
+            asc: Some(!o.descending),
+            nulls_first: None,
+        })
+        .collect();
+    let query = query(
+        projection,
+        table,
+        selection,
+        order_by,
+        options.limit,
+        options.offset,
+    );
+    (Statement::Query(Box::new(query)).to_string(), params)
+}
+
+fn query(
+    projection: Vec<SelectItem>,
+    table: &str,
+    selection: Option<Expr>,
+    order_by: Vec<OrderByExpr>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Query {
+    // SQLite only takes an OFFSET after a LIMIT, where -1 means no limit
+    let limit = match (limit, offset) {
+        (Some(limit), _) => Some(number(limit as i64)),
+        (None, Some(_)) => Some(number(-1)),
+        (None, None) => None,
+    };
+    Query {
+        with: None,
+        body: Box::new(SetExpr::Select(Box::new(Select {
+            distinct: None,
+            top: None,
+            projection,
+            into: None,
+            from: vec![TableWithJoins {
+                relation: TableFactor::Table {
+                    name: ObjectName(vec![quoted(table)]),
+                    alias: None,
+                    args: None,
+                    with_hints: vec![],
+                    version: None,
+                },
+                joins: vec![],
+            }],
+            lateral_views: vec![],
+            selection,
+            group_by: vec![],
+            cluster_by: vec![],
+            distribute_by: vec![],
+            sort_by: vec![],
+            having: None,
+            named_window: vec![],
+            qualify: None,
+        }))),
+        order_by,
+        limit,
+        offset: offset.map(|offset| Offset {
+            value: number(offset as i64),
+            rows: OffsetRows::None,
+        }),
+        fetch: None,
+        locks: vec![],
+    }
+}
+
+fn quoted(name: &str) -> Ident {
+    Ident::with_quote('"', name)
+}
+
+fn column_expr(column: &str) -> Expr {
+    Expr::Identifier(quoted(column))
+}
+
+fn number(n: i64) -> Expr {
+    Expr::Value(SqlValue::Number(n.to_string(), false))
+}
+
+/// A call returning `value`, which is added to `params`.
+fn param(value: &Value, params: &mut Vec<Value>) -> Expr {
+    // SQLite stores NaN as NULL, as it would read the literal
+    let value = match value {
+        Value::Real(f) if f.is_nan() => Value::Null,
+        value => value.clone(),
+    };
+    params.push(value);
+    call(PARAM_FN, [number(params.len() as i64 - 1)])
+}
+
+fn call(name: &str, args: impl IntoIterator<Item = Expr>) -> Expr {
+    Expr::Function(Function {
+        name: ObjectName(vec![Ident::new(name)]),
+        args: args
+            .into_iter()
+            .map(|arg| FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)))
+            .collect(),
+        over: None,
+        distinct: false,
+        order_by: vec![],
+        special: false,
+    })
+}
+
+fn conjunction(exprs: impl Iterator<Item = Expr>) -> Option<Expr> {
+    exprs.reduce(|left, right| Expr::BinaryOp {
+        left: Box::new(left),
+        op: BinaryOperator::And,
+        right: Box::new(right),
+    })
+}
+
+/// A hash of a rowid under a seed, which orders rows for [`Sample`]. The seed is hashed first
+/// so that nearby seeds pick unrelated samples.
+fn sample_key(rowid: i64, seed: i64) -> i64 {
+    splitmix64(splitmix64(seed as u64) ^ rowid as u64) as i64
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
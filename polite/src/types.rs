@@ -1,19 +1,58 @@
-use polars::prelude::{DataType, Schema};
+use crate::blob::is_uuid_decl;
+use crate::connectorx::constants::{DEFAULT_ARROW_DECIMAL_PRECISION, DEFAULT_ARROW_DECIMAL_SCALE};
+use crate::connectorx::sources::sqlite::{DecimalDecl, SQLiteTypeSystem};
+use crate::options::ReadOptions;
+use polars::prelude::{DataType, Schema, TimeUnit};
+use rusqlite::types::Type;
 use rusqlite::Statement;
 
 /// Polars Schema from SQLite query (parsed from a string by `Connection::prepare`)
-pub(crate) fn schema_from_sqlite(stmt: &Statement) -> Schema {
-    Schema::from_iter(stmt.columns().iter().map(|col| {
-        let name = col.name();
-        let decl_type = col.decl_type();
+pub(crate) fn schema_from_sqlite(stmt: &Statement, options: &ReadOptions) -> Schema {
+    Schema::from_iter(
+        stmt.columns()
+            .iter()
+            .map(|col| (col.name().into(), decl_dtype(col.decl_type(), options))),
+    )
+}
 
-        let dtype = match decl_type {
-            Some("INTEGER") => DataType::Int64,
-            Some("REAL") => DataType::Float64,
-            Some("TEXT") => DataType::String,
-            _ => DataType::String,
-        };
+/// The Polars dtype a column with the given declared type is read into, when there is no row
+/// to look at. Undeclared and unrecognised types are read as text.
+pub(crate) fn decl_dtype(decl_type: Option<&str>, options: &ReadOptions) -> DataType {
+    match SQLiteTypeSystem::try_from((decl_type, Type::Null)) {
+        _ if options.decode_uuids && is_uuid_decl(decl_type) => DataType::String,
+        Ok(SQLiteTypeSystem::Decimal(_)) => match decl_type.and_then(DecimalDecl::parse) {
+            Some(d) => DataType::Decimal(Some(d.precision as usize), Some(d.scale as usize)),
+            None => polars_dtype(SQLiteTypeSystem::Decimal(true)),
+        },
+        Ok(ty) if options.widen_integers => polars_dtype(ty.widened()),
+        Ok(ty) => polars_dtype(ty),
+        Err(_) => DataType::String,
+    }
+}
 
-        (name.into(), dtype)
-    }))
+/// The Polars dtype a column of the given SQLite type is read into.
+pub(crate) fn polars_dtype(ty: SQLiteTypeSystem) -> DataType {
+    use SQLiteTypeSystem::*;
+    match ty {
+        Bool(_) => DataType::Boolean,
+        Int8(_) => DataType::Int64,
+        Int4(_) => DataType::Int32,
+        Int2(_) => DataType::Int16,
+        Int1(_) => DataType::Int8,
+        UInt8(_) => DataType::UInt64,
+        UInt4(_) => DataType::UInt32,
+        UInt2(_) => DataType::UInt16,
+        UInt1(_) => DataType::UInt8,
+        Real(_) => DataType::Float64,
+        // Only the default: declared precision and scale are applied per column
+        Decimal(_) => DataType::Decimal(
+            Some(DEFAULT_ARROW_DECIMAL_PRECISION as usize),
+            Some(DEFAULT_ARROW_DECIMAL_SCALE as usize),
+        ),
+        Text(_) => DataType::String,
+        Date(_) => DataType::Date,
+        Time(_) => DataType::Time,
+        Timestamp(_) => DataType::Datetime(TimeUnit::Nanoseconds, None),
+        Blob(_) => DataType::Binary,
+    }
 }
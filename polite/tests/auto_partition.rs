@@ -0,0 +1,107 @@
+// polite/tests/auto_partition.rs
+mod common;
+
+use common::read_partitions;
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::{AutoPartition, ReadThreads};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+
+fn db() -> NamedTempFile {
+    common::db(
+        "CREATE TABLE plain (name TEXT, n INTEGER);
+         CREATE TABLE keyed (id INTEGER PRIMARY KEY, n INTEGER);
+         CREATE TABLE clustered (id INTEGER PRIMARY KEY, n INTEGER) WITHOUT ROWID;
+         WITH RECURSIVE s(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM s WHERE i < 1000)
+         INSERT INTO plain SELECT 'row ' || i, i FROM s;
+         INSERT INTO keyed SELECT n * 10, n FROM plain;
+         INSERT INTO clustered SELECT id, n FROM keyed;
+         CREATE VIEW evens AS SELECT * FROM plain WHERE n % 2 = 0;",
+    )
+}
+
+fn read(tmp: &NamedTempFile, sql: &str, auto_partition: AutoPartition) -> (DataFrame, Vec<usize>) {
+    let options = ReadOptions {
+        auto_partition,
+        ..Default::default()
+    };
+    read_partitions(tmp, sql, options)
+}
+
+fn sum(df: &DataFrame, column: &str) -> i64 {
+    df.column(column).unwrap().i64().unwrap().sum().unwrap()
+}
+
+#[test]
+fn test_table_scans_are_split_by_rowid() {
+    let tmp = db();
+    let (df, sizes) = read(&tmp, "SELECT * FROM plain", AutoPartition::Ranges(4));
+    assert_eq!(sizes, [250, 250, 250, 250]);
+    assert_eq!(sum(&df, "n"), 500_500);
+
+    // The INTEGER PRIMARY KEY is the rowid; the query's own filter still applies
+    let (df, sizes) = read(
+        &tmp,
+        "SELECT id, upper(CAST(n AS TEXT)) AS label FROM keyed WHERE n > 100",
+        AutoPartition::Ranges(3),
+    );
+    assert_eq!(sizes.len(), 3);
+    assert_eq!(df.height(), 900);
+    assert_eq!(sum(&df, "id"), (101..=1000).sum::<i64>() * 10);
+
+    let (df, _) = read(&tmp, "SELECT * FROM plain", AutoPartition::Cores);
+    assert_eq!(df.height(), 1000);
+}
+
+#[test]
+fn test_cores_follow_the_read_threads() {
+    let tmp = db();
+    let pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap(),
+    );
+    for (threads, num) in [(ReadThreads::Count(3), 3), (ReadThreads::Pool(pool), 2)] {
+        let options = ReadOptions {
+            auto_partition: AutoPartition::Cores,
+            threads,
+            ..Default::default()
+        };
+        let (df, sizes) = read_partitions(&tmp, "SELECT * FROM plain", options);
+        assert_eq!(df.height(), 1000);
+        assert_eq!(sizes.len(), num);
+    }
+}
+
+#[test]
+fn test_other_reads_stay_whole() {
+    let tmp = db();
+    for sql in [
+        "SELECT * FROM evens",
+        "SELECT * FROM clustered",
+        "SELECT * FROM plain ORDER BY n DESC",
+        "SELECT p.name, k.id FROM plain p JOIN keyed k ON k.n = p.n",
+        "SELECT DISTINCT n % 10 AS d FROM plain",
+        "SELECT * FROM plain LIMIT 10",
+    ] {
+        let (_, sizes) = read(&tmp, sql, AutoPartition::Ranges(4));
+        assert_eq!(sizes.len(), 1, "{sql}");
+    }
+
+    let (df, sizes) = read(
+        &tmp,
+        "SELECT count(*) AS rows, sum(n) AS total FROM plain",
+        AutoPartition::Ranges(4),
+    );
+    assert_eq!(sizes.len(), 1);
+    assert_eq!(sum(&df, "rows"), 1000);
+
+    let (df, _) = read(
+        &tmp,
+        "SELECT n FROM plain ORDER BY n DESC",
+        AutoPartition::Ranges(4),
+    );
+    assert_eq!(df.column("n").unwrap().i64().unwrap().get(0), Some(1000));
+}
@@ -0,0 +1,130 @@
+// polite/tests/blob.rs
+use polars::prelude::*;
+use polite::blob::{encode_blobs, BlobEncoding};
+use polite::prelude::*;
+use tempfile::NamedTempFile;
+
+const UUID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+#[test]
+fn test_binary_roundtrip_as_blob() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let bytes: Vec<Option<&[u8]>> = vec![Some(b"\x00\xffabc"), None];
+    let df = DataFrame::new(vec![Column::new("data".into(), bytes)]).unwrap();
+    from_dataframe(&conn, "t", &df).unwrap();
+
+    let ty: String = conn
+        .query_row("SELECT typeof(data) FROM t LIMIT 1", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(ty, "blob");
+
+    let back = to_dataframe(db_path, "SELECT data FROM t").unwrap();
+    assert!(back.equals_missing(&df), "{back:?} != {df:?}");
+}
+
+#[test]
+fn test_uuid_decl_decodes_to_string() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch(
+        "CREATE TABLE t (id UUID, other BLOB(16));
+         INSERT INTO t VALUES (
+             X'67e5504410b1426f9247bb680e5fe0c8',
+             X'00000000000000000000000000000001'
+         );",
+    )
+    .unwrap();
+
+    // Left as binary unless asked for
+    let df = to_dataframe(db_path, "SELECT id FROM t").unwrap();
+    assert_eq!(df.column("id").unwrap().dtype(), &DataType::Binary);
+
+    let options = ReadOptions {
+        decode_uuids: true,
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(db_path, "SELECT * FROM t", &options).unwrap();
+    let id = df.column("id").unwrap().str().unwrap().get(0);
+    let other = df.column("other").unwrap().str().unwrap().get(0);
+    assert_eq!(id, Some(UUID));
+    assert_eq!(other, Some("00000000-0000-0000-0000-000000000001"));
+
+    let empty = to_dataframe_with_options(db_path, "SELECT id FROM t WHERE 0", &options).unwrap();
+    assert_eq!(empty.column("id").unwrap().dtype(), &DataType::String);
+}
+
+#[test]
+fn test_uuid_wrong_length_is_an_error() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch("CREATE TABLE t (id UUID); INSERT INTO t VALUES (X'0102');")
+        .unwrap();
+
+    let options = ReadOptions {
+        decode_uuids: true,
+        ..Default::default()
+    };
+    let err = to_dataframe_with_options(db_path, "SELECT id FROM t", &options).unwrap_err();
+    assert!(
+        matches!(err, PoliteError::InvalidUuid { row: 0, .. }),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_uuid_columns_roundtrip() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let df = df! { "id" => [Some(UUID), None] }.unwrap();
+    let options = WriteOptions {
+        uuid_columns: vec!["id".to_string()],
+        ..Default::default()
+    };
+    from_dataframe_with_options(&conn, "t", &df, &options).unwrap();
+
+    let (ty, len): (String, i64) = conn
+        .query_row("SELECT typeof(id), length(id) FROM t LIMIT 1", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .unwrap();
+    assert_eq!((ty.as_str(), len), ("blob", 16));
+
+    let read = ReadOptions {
+        decode_uuids: true,
+        ..Default::default()
+    };
+    let back = to_dataframe_with_options(db_path, "SELECT id FROM t", &read).unwrap();
+    assert!(back.equals_missing(&df), "{back:?} != {df:?}");
+
+    let bad = df! { "id" => ["not-a-uuid"] }.unwrap();
+    let err = from_dataframe_with_options(&conn, "t", &bad, &options).unwrap_err();
+    assert!(err.to_string().contains("not a valid UUID"), "{err}");
+
+    // Signs are not hex digits, even where they would parse as a signed byte
+    let signed = df! { "id" => ["+f+f+f+f-+f+f-+f+f-+f+f-+f+f+f+f+f+f"] }.unwrap();
+    let err = from_dataframe_with_options(&conn, "t", &signed, &options).unwrap_err();
+    assert!(err.to_string().contains("not a valid UUID"), "{err}");
+}
+
+#[test]
+fn test_encode_blobs_for_display() {
+    let bytes: Vec<&[u8]> = vec![b"hi!", b"\xff", b""];
+    let df = DataFrame::new(vec![Column::new("b".into(), bytes)]).unwrap();
+
+    let hex = encode_blobs(&df, BlobEncoding::Hex).unwrap();
+    let hex: Vec<_> = hex.column("b").unwrap().str().unwrap().iter().collect();
+    assert_eq!(hex, [Some("686921"), Some("ff"), Some("")]);
+
+    let b64 = encode_blobs(&df, BlobEncoding::Base64).unwrap();
+    let b64: Vec<_> = b64.column("b").unwrap().str().unwrap().iter().collect();
+    assert_eq!(b64, [Some("aGkh"), Some("/w=="), Some("")]);
+}
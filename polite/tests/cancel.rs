@@ -0,0 +1,99 @@
+// polite/tests/cancel.rs
+mod common;
+
+use polite::prelude::*;
+use std::time::{Duration, Instant};
+use tempfile::NamedTempFile;
+
+const SLOW_QUERY: &str = "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) \
+                          SELECT x FROM n WHERE x < 0";
+
+fn small_db() -> NamedTempFile {
+    common::db("CREATE TABLE t (id INTEGER); INSERT INTO t VALUES (1), (2), (3);")
+}
+
+#[test]
+fn test_timeout_interrupts_read() {
+    let tmp = small_db();
+    let db_path = tmp.path().to_str().unwrap();
+    let opts = ReadOptions {
+        timeout: Some(Duration::from_millis(200)),
+        ..Default::default()
+    };
+
+    let start = Instant::now();
+    let err = to_dataframe_with_options(db_path, SLOW_QUERY, &opts).unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(10));
+    match err {
+        PoliteError::Cancelled {
+            timed_out, rows, ..
+        } => {
+            assert!(timed_out);
+            assert_eq!(rows.get_column_names(), ["x"]);
+        }
+        other => panic!("expected Cancelled, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_cancel_from_another_thread() {
+    let tmp = small_db();
+    let db_path = tmp.path().to_str().unwrap();
+    let token = CancellationToken::new();
+    let opts = ReadOptions {
+        cancel: Some(token.clone()),
+        ..Default::default()
+    };
+
+    let canceller = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(200));
+        token.cancel();
+    });
+    let err = to_dataframe_with_options(db_path, SLOW_QUERY, &opts).unwrap_err();
+    canceller.join().unwrap();
+    assert!(matches!(
+        err,
+        PoliteError::Cancelled {
+            timed_out: false,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_cancelled_token_stops_read() {
+    let tmp = small_db();
+    let db_path = tmp.path().to_str().unwrap();
+    let token = CancellationToken::new();
+    token.cancel();
+    let opts = ReadOptions {
+        cancel: Some(token),
+        ..Default::default()
+    };
+
+    let err = to_dataframe_with_options(db_path, "SELECT id FROM t", &opts).unwrap_err();
+    match err {
+        PoliteError::Cancelled { rows, .. } => assert_eq!(rows.height(), 0),
+        other => panic!("expected Cancelled, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_read_within_timeout_completes() {
+    let tmp = small_db();
+    let db_path = tmp.path().to_str().unwrap();
+    let token = CancellationToken::new();
+    let opts = ReadOptions {
+        timeout: Some(Duration::from_secs(60)),
+        cancel: Some(token.clone()),
+        ..Default::default()
+    };
+
+    let df = to_dataframe_with_options(db_path, "SELECT id FROM t", &opts).unwrap();
+    assert_eq!(df.height(), 3);
+    assert!(!token.is_cancelled());
+
+    // The same token can be reused for later reads
+    let df = to_dataframe_with_options(db_path, "SELECT id FROM t", &opts).unwrap();
+    assert_eq!(df.height(), 3);
+}
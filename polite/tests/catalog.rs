@@ -0,0 +1,117 @@
+// polite/tests/catalog.rs
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::Catalog;
+use tempfile::TempDir;
+
+struct Files {
+    _dir: TempDir,
+    main: String,
+    raw: String,
+    staging: String,
+}
+
+fn files() -> Files {
+    let dir = TempDir::new().unwrap();
+    let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+    let (main, raw, staging) = (path("main.db"), path("raw?#%.db"), path("staging.db"));
+    let users = df! { "id" => [1i64, 2], "name" => ["Alice", "Bob"] }.unwrap();
+    save_dataframe(&raw, "users", &users).unwrap();
+    Files {
+        _dir: dir,
+        main,
+        raw,
+        staging,
+    }
+}
+
+fn catalog(files: &Files) -> Catalog {
+    Catalog::new(&files.main)
+        .attach_read_only("raw", &files.raw)
+        .attach("staging", &files.staging)
+}
+
+#[test]
+fn test_cross_database_join() {
+    let files = files();
+    let catalog = catalog(&files);
+    let events = df! { "user_id" => [2i64, 1, 2], "kind" => ["a", "b", "c"] }.unwrap();
+    catalog.from_dataframe("staging.events", &events).unwrap();
+
+    let sql = "SELECT u.name, count(*) AS n FROM raw.users u
+               JOIN staging.events e ON e.user_id = u.id GROUP BY u.name ORDER BY u.name";
+    let df = catalog.to_dataframe(sql).unwrap();
+    let expected = df! { "name" => ["Alice", "Bob"], "n" => [1i64, 2] }.unwrap();
+    assert!(df.equals(&expected), "{df}");
+
+    // The write went to the staging file, not the main database
+    let staging = connect_sqlite(Some(&files.staging)).unwrap();
+    let count: i64 = staging
+        .query_row("SELECT count(*) FROM events", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn test_write_honours_connect_options() {
+    let files = files();
+    let catalog = Catalog::new(&files.main).attach("staging", &files.staging);
+    let events = df! { "id" => [1i64, 2] }.unwrap();
+    let wal = ConnectOptions::default().journal_mode(polite::options::JournalMode::Wal);
+    catalog
+        .from_dataframe_with_options("events", &events, &wal, &WriteOptions::default())
+        .unwrap();
+
+    // The mode is set on the main database and the attached one
+    for path in [&files.main, &files.staging] {
+        let conn = connect_sqlite(Some(path)).unwrap();
+        let mode: String = conn
+            .query_row("PRAGMA journal_mode", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+    }
+}
+
+#[test]
+fn test_partitioned_read_attaches_on_every_connection() {
+    let files = files();
+    let catalog = catalog(&files);
+    let n = 1000;
+    let events = df! { "id" => (0..n).collect::<Vec<i64>>() }.unwrap();
+    catalog.from_dataframe("staging.events", &events).unwrap();
+
+    let options = ReadOptions {
+        partition_on: Some(polite::PartitionOn::new("id", 4)),
+        ..Default::default()
+    };
+    let df = catalog
+        .to_dataframe_with_options("SELECT id FROM staging.events", &options)
+        .unwrap();
+    assert_eq!(df.height(), n as usize);
+}
+
+#[test]
+fn test_read_only_alias_refuses_writes() {
+    let files = files();
+    let catalog = catalog(&files);
+    let more = df! { "id" => [3i64], "name" => ["Carol"] }.unwrap();
+    let err = catalog.from_dataframe("raw.users", &more).unwrap_err();
+    assert!(err.to_string().contains("readonly"), "{err}");
+
+    let conn = catalog.connect().unwrap();
+    assert!(conn.execute_batch("DELETE FROM raw.users").is_err());
+    assert!(conn
+        .execute_batch("CREATE TABLE staging.t (x INTEGER)")
+        .is_ok());
+}
+
+#[test]
+fn test_connect_options_attach() {
+    let files = files();
+    let options = ConnectOptions::default().attach_read_only("raw", &files.raw);
+    let conn = connect_sqlite_with_options(Some(&files.main), &options).unwrap();
+    let count: i64 = conn
+        .query_row("SELECT count(*) FROM raw.users", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, 2);
+}
@@ -0,0 +1,131 @@
+// polite/tests/categorical.rs
+mod common;
+
+use common::strings;
+use polars::prelude::*;
+use polite::prelude::*;
+use tempfile::NamedTempFile;
+
+fn status_db() -> NamedTempFile {
+    common::db(
+        "CREATE TABLE t (id INTEGER, status TEXT, name TEXT);
+         INSERT INTO t VALUES (1, 'open', 'a');
+         INSERT INTO t VALUES (2, 'closed', 'b');
+         INSERT INTO t VALUES (3, 'open', 'c');
+         INSERT INTO t VALUES (4, NULL, 'd');
+         INSERT INTO t VALUES (5, 'open', 'e');",
+    )
+}
+
+fn is_categorical(df: &DataFrame, name: &str) -> bool {
+    matches!(
+        df.column(name).unwrap().dtype(),
+        DataType::Categorical(_, _)
+    )
+}
+
+#[test]
+fn test_categorical_columns_option() {
+    let db = status_db();
+    let options = ReadOptions {
+        categorical_columns: vec!["status".to_string()],
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(db.path().to_str().unwrap(), "SELECT * FROM t", &options)
+        .unwrap();
+
+    assert!(is_categorical(&df, "status"));
+    assert_eq!(df.column("name").unwrap().dtype(), &DataType::String);
+    assert_eq!(
+        strings(&df, "status"),
+        [
+            Some("open"),
+            Some("closed"),
+            Some("open"),
+            None,
+            Some("open")
+        ]
+        .map(|v| v.map(str::to_string))
+    );
+}
+
+#[test]
+fn test_categorical_text_applies_to_every_text_column() {
+    let db = status_db();
+    let options = ReadOptions {
+        categorical_text: true,
+        ..Default::default()
+    };
+    let path = db.path().to_str().unwrap();
+    let df = to_dataframe_with_options(path, "SELECT * FROM t", &options).unwrap();
+    assert!(is_categorical(&df, "status"));
+    assert!(is_categorical(&df, "name"));
+    assert_eq!(df.column("id").unwrap().dtype(), &DataType::Int64);
+
+    let empty = to_dataframe_with_options(path, "SELECT * FROM t WHERE 0", &options).unwrap();
+    assert!(is_categorical(&empty, "status"));
+}
+
+#[test]
+fn test_enum_columns_option() {
+    let db = status_db();
+    let path = db.path().to_str().unwrap();
+    let categories = vec!["open".to_string(), "closed".to_string()];
+    let options = ReadOptions {
+        enum_columns: vec![("status".to_string(), categories.clone())],
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(path, "SELECT status FROM t", &options).unwrap();
+
+    let expected = create_enum_dtype(polars_arrow::array::Utf8ViewArray::from_slice_values(
+        &categories,
+    ));
+    assert_eq!(df.column("status").unwrap().dtype(), &expected);
+    assert_eq!(strings(&df, "status")[1].as_deref(), Some("closed"));
+
+    // A value outside the categories is an error
+    let options = ReadOptions {
+        enum_columns: vec![("status".to_string(), vec!["open".to_string()])],
+        ..Default::default()
+    };
+    assert!(to_dataframe_with_options(path, "SELECT status FROM t", &options).is_err());
+}
+
+#[test]
+fn test_categorical_threshold_detects_low_cardinality() {
+    let db = status_db();
+    let options = ReadOptions {
+        // status has 2 distinct of 4 non-null values, name 5 of 5
+        categorical_threshold: Some(0.5),
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(db.path().to_str().unwrap(), "SELECT * FROM t", &options)
+        .unwrap();
+    assert!(is_categorical(&df, "status"));
+    assert_eq!(df.column("name").unwrap().dtype(), &DataType::String);
+}
+
+#[test]
+fn test_categorical_across_record_batches() {
+    let db = NamedTempFile::new().unwrap();
+    let path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(path)).unwrap();
+    // More rows than one 64K record batch, so the dictionaries of each batch are merged
+    conn.execute_batch(
+        "CREATE TABLE t (c TEXT);
+         WITH RECURSIVE n(i) AS (SELECT 0 UNION ALL SELECT i + 1 FROM n WHERE i < 69999)
+         INSERT INTO t SELECT CASE WHEN i < 66000 THEN 'x' || (i % 3) ELSE 'y' || (i % 2) END
+         FROM n;",
+    )
+    .unwrap();
+
+    let options = ReadOptions {
+        categorical_columns: vec!["c".to_string()],
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(path, "SELECT c FROM t", &options).unwrap();
+    assert!(is_categorical(&df, "c"));
+    assert_eq!(df.height(), 70_000);
+    assert_eq!(df.column("c").unwrap().n_unique().unwrap(), 5);
+    assert_eq!(strings(&df, "c")[69_999].as_deref(), Some("y1"));
+}
@@ -0,0 +1,59 @@
+// polite/tests/common/mod.rs
+//! Fixtures shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::progress::{Phase, ProgressObserver};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tempfile::NamedTempFile;
+
+/// A database file set up by `sql`, removed when dropped.
+pub fn db(sql: &str) -> NamedTempFile {
+    let tmp = NamedTempFile::new().unwrap();
+    let conn = connect_sqlite(tmp.path().to_str()).unwrap();
+    conn.execute_batch(sql).unwrap();
+    tmp
+}
+
+/// A column's values as strings, casting Categorical and Enum columns.
+pub fn strings(df: &DataFrame, column: &str) -> Vec<Option<String>> {
+    let column = df.column(column).unwrap().cast(&DataType::String).unwrap();
+    column
+        .str()
+        .unwrap()
+        .iter()
+        .map(|v| v.map(str::to_string))
+        .collect()
+}
+
+/// The rows each partition fetched, by partition.
+#[derive(Default)]
+pub struct PartitionRows(Mutex<BTreeMap<usize, usize>>);
+
+impl ProgressObserver for PartitionRows {
+    fn phase(&self, _phase: Phase) {}
+
+    fn rows_fetched(&self, partition: usize, rows: usize, _total: Option<usize>) {
+        self.0.lock().unwrap().insert(partition, rows);
+    }
+
+    fn rows_written(&self, _table: &str, _rows: usize, _total: usize) {}
+}
+
+/// Read `sql` with `options`, returning the rows each partition fetched alongside the result.
+pub fn read_partitions(
+    tmp: &NamedTempFile,
+    sql: &str,
+    options: ReadOptions,
+) -> (DataFrame, Vec<usize>) {
+    let rows = Arc::new(PartitionRows::default());
+    let options = ReadOptions {
+        progress: Some(rows.clone()),
+        ..options
+    };
+    let df = to_dataframe_with_options(tmp.path().to_str().unwrap(), sql, &options).unwrap();
+    let sizes = rows.0.lock().unwrap().values().copied().collect();
+    (df, sizes)
+}
@@ -0,0 +1,118 @@
+// polite/tests/connect.rs
+use polite::options::{JournalMode, LockingMode, Synchronous};
+use polite::prelude::*;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+
+fn pragma(conn: &rusqlite::Connection, name: &str) -> String {
+    conn.query_row(&format!("PRAGMA {name}"), [], |row| {
+        row.get::<_, rusqlite::types::Value>(0)
+    })
+    .map(|v| match v {
+        rusqlite::types::Value::Integer(i) => i.to_string(),
+        rusqlite::types::Value::Text(t) => t,
+        other => format!("{other:?}"),
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_connect_applies_pragmas() {
+    let tmp = NamedTempFile::new().unwrap();
+    let opts = ConnectOptions::default()
+        .journal_mode(JournalMode::Wal)
+        .synchronous(Synchronous::Normal)
+        .foreign_keys(true)
+        .cache_size(-4000)
+        .mmap_size(1 << 20)
+        .locking_mode(LockingMode::Normal)
+        .busy_timeout(Duration::from_millis(250));
+    let conn = connect_sqlite_with_options(tmp.path().to_str(), &opts).unwrap();
+
+    assert_eq!(pragma(&conn, "journal_mode"), "wal");
+    assert_eq!(pragma(&conn, "synchronous"), "1");
+    assert_eq!(pragma(&conn, "foreign_keys"), "1");
+    assert_eq!(pragma(&conn, "cache_size"), "-4000");
+    assert_eq!(pragma(&conn, "busy_timeout"), "250");
+}
+
+#[test]
+fn test_read_only_and_no_create() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    execute_query(
+        &connect_sqlite(Some(db_path)).unwrap(),
+        "CREATE TABLE t (id INTEGER)",
+    )
+    .unwrap();
+
+    let conn =
+        connect_sqlite_with_options(Some(db_path), &ConnectOptions::default().read_only(true))
+            .unwrap();
+    let err = execute_query(&conn, "INSERT INTO t VALUES (1)").unwrap_err();
+    assert!(matches!(err, PoliteError::Exec { .. }));
+
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.db");
+    let err =
+        connect_sqlite_with_options(missing.to_str(), &ConnectOptions::default().create(false))
+            .unwrap_err();
+    assert!(matches!(err, PoliteError::Connect { .. }));
+    assert!(!missing.exists());
+}
+
+#[test]
+fn test_read_connections_honour_options() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    execute_query(
+        &connect_sqlite(Some(db_path)).unwrap(),
+        "CREATE TABLE t (id INTEGER)",
+    )
+    .unwrap();
+
+    let opts = ReadOptions {
+        connect: ConnectOptions::default().read_only(true).cache_size(-1234),
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(
+        db_path,
+        "SELECT cache_size, (SELECT count(*) FROM t) AS n FROM pragma_cache_size",
+        &opts,
+    )
+    .unwrap();
+    assert_eq!(
+        df.column("cache_size").unwrap().i64().unwrap().get(0),
+        Some(-1234)
+    );
+
+    // A read-only read can't reach a database that doesn't exist yet
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.db");
+    let err = to_dataframe_with_options(missing.to_str().unwrap(), "SELECT 1", &opts).unwrap_err();
+    assert!(matches!(err, PoliteError::Connect { .. }));
+}
+
+#[test]
+fn test_save_honours_connect_options() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    let df = polars::df! { "id" => [1i64, 2] }.unwrap();
+    let wal = ConnectOptions::default().journal_mode(JournalMode::Wal);
+    save_dataframe_with_options(db_path, "t", &df, &wal, &WriteOptions::default()).unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    assert_eq!(pragma(&conn, "journal_mode"), "wal");
+
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.db");
+    let err = save_dataframe_with_options(
+        missing.to_str().unwrap(),
+        "t",
+        &df,
+        &ConnectOptions::default().create(false),
+        &WriteOptions::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, PoliteError::Connect { .. }), "{err}");
+    assert!(!missing.exists());
+}
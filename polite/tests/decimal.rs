@@ -0,0 +1,227 @@
+// polite/tests/decimal.rs
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::DecimalStorage;
+use tempfile::NamedTempFile;
+
+fn decimal_values(df: &DataFrame, col: &str) -> Vec<Option<String>> {
+    let ca = df.column(col).unwrap().decimal().unwrap();
+    (0..ca.len())
+        .map(|i| ca.get_any_value(i).unwrap())
+        .map(|v| match v {
+            AnyValue::Null => None,
+            v => Some(v.to_string()),
+        })
+        .collect()
+}
+
+#[test]
+fn test_numeric_reads_as_exact_decimal() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch(
+        "CREATE TABLE t (price NUMERIC(12,2));
+         INSERT INTO t VALUES ('19.99');
+         INSERT INTO t VALUES (5);
+         INSERT INTO t VALUES (0.1);
+         INSERT INTO t VALUES (NULL);",
+    )
+    .unwrap();
+
+    let df = to_dataframe(db_path, "SELECT price FROM t").unwrap();
+    assert_eq!(
+        df.column("price").unwrap().dtype(),
+        &DataType::Decimal(Some(12), Some(2))
+    );
+    assert_eq!(
+        decimal_values(&df, "price"),
+        vec![
+            Some("19.99".to_string()),
+            Some("5.00".to_string()),
+            Some("0.10".to_string()),
+            None
+        ]
+    );
+}
+
+#[test]
+fn test_decimal_empty_result_keeps_precision() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute("CREATE TABLE t (amount DECIMAL(10,4))", [])
+        .unwrap();
+
+    let df = to_dataframe(db_path, "SELECT amount FROM t").unwrap();
+    assert_eq!(df.height(), 0);
+    assert_eq!(
+        df.column("amount").unwrap().dtype(),
+        &DataType::Decimal(Some(10), Some(4))
+    );
+}
+
+#[test]
+fn test_invalid_decimal_text_is_an_error() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch(
+        "CREATE TABLE t (price DECIMAL(8,2));
+         INSERT INTO t VALUES ('1.50');
+         INSERT INTO t VALUES ('abc');",
+    )
+    .unwrap();
+
+    let err = to_dataframe(db_path, "SELECT price FROM t").unwrap_err();
+    let msg = format!("{err:?}");
+    assert!(msg.contains("abc"), "unexpected error: {msg}");
+}
+
+fn roundtrip(storage: DecimalStorage) {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let df = df! { "amount" => ["12.30", "-0.05", "1000.00"] }
+        .unwrap()
+        .lazy()
+        .with_column(col("amount").cast(DataType::Decimal(Some(10), Some(2))))
+        .collect()
+        .unwrap();
+
+    let options = WriteOptions {
+        decimal_storage: storage,
+        ..Default::default()
+    };
+    from_dataframe_with_options(&conn, "t", &df, &options).unwrap();
+
+    let back = to_dataframe(db_path, "SELECT amount FROM t").unwrap();
+    assert_eq!(
+        back.column("amount").unwrap().dtype(),
+        &DataType::Decimal(Some(10), Some(2))
+    );
+    assert_eq!(
+        decimal_values(&back, "amount"),
+        vec![
+            Some("12.30".to_string()),
+            Some("-0.05".to_string()),
+            Some("1000.00".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_decimal_roundtrip_as_text() {
+    roundtrip(DecimalStorage::Text);
+}
+
+#[test]
+fn test_decimal_roundtrip_as_scaled_integer() {
+    roundtrip(DecimalStorage::ScaledInteger);
+}
+
+#[test]
+fn test_decimal_storage_classes() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let df = df! { "a" => ["1.25"] }
+        .unwrap()
+        .lazy()
+        .with_column(col("a").cast(DataType::Decimal(Some(6), Some(2))))
+        .collect()
+        .unwrap();
+
+    from_dataframe(&conn, "text_t", &df).unwrap();
+    let options = WriteOptions {
+        decimal_storage: DecimalStorage::ScaledInteger,
+        ..Default::default()
+    };
+    from_dataframe_with_options(&conn, "int_t", &df, &options).unwrap();
+
+    let (ty, text): (String, String) = conn
+        .query_row("SELECT typeof(a), a FROM text_t", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .unwrap();
+    assert_eq!((ty.as_str(), text.as_str()), ("text", "1.25"));
+
+    let (ty, scaled): (String, i64) = conn
+        .query_row("SELECT typeof(a), a FROM int_t", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .unwrap();
+    assert_eq!((ty.as_str(), scaled), ("integer", 125));
+}
+
+#[test]
+fn test_decimal_wider_than_28_digits() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch(
+        "CREATE TABLE t (id INTEGER, amount DECIMAL_TEXT(36,2));
+         INSERT INTO t VALUES (1, '1234567890123456789012345.67');
+         INSERT INTO t VALUES (2, '1234567890123456789012345678901234.56');",
+    )
+    .unwrap();
+
+    // Values with up to 28 digits are read exactly, rather than as Float64
+    let df = to_dataframe(db_path, "SELECT amount FROM t WHERE id = 1").unwrap();
+    assert_eq!(
+        df.column("amount").unwrap().dtype(),
+        &DataType::Decimal(Some(36), Some(2))
+    );
+    assert_eq!(
+        decimal_values(&df, "amount"),
+        vec![Some("1234567890123456789012345.67".to_string())]
+    );
+
+    // Longer ones can't be, and fail to convert instead of losing digits
+    let err = to_dataframe(db_path, "SELECT amount FROM t").unwrap_err();
+    let msg = err.to_string();
+    assert!(
+        msg.contains("significant digits"),
+        "unexpected error: {msg}"
+    );
+    assert!(
+        msg.contains("12345678901234567890"),
+        "unexpected error: {msg}"
+    );
+}
+
+#[test]
+fn test_decimal_scale_wider_than_28_digits() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch(
+        "CREATE TABLE t (id INTEGER, ratio DECIMAL(38,30), scaled DECIMAL_INT(38,30));
+         INSERT INTO t VALUES (1, '1.5', 7);
+         INSERT INTO t VALUES (2, '-0.0000000000000000000000000025', 8);",
+    )
+    .unwrap();
+
+    // No value can have more than 28 decimal places, so the column is read at that scale
+    let df = to_dataframe(db_path, "SELECT ratio, scaled FROM t ORDER BY id").unwrap();
+    assert_eq!(
+        df.column("ratio").unwrap().dtype(),
+        &DataType::Decimal(Some(38), Some(28))
+    );
+    assert_eq!(
+        decimal_values(&df, "ratio"),
+        vec![
+            Some(format!("1.5{}", "0".repeat(27))),
+            Some("-0.0000000000000000000000000025".to_string()),
+        ]
+    );
+    // Integers scaled by 10^30 can't be, so they are read as they are stored
+    assert_eq!(df.column("scaled").unwrap().dtype(), &DataType::Int64);
+}
@@ -54,3 +54,63 @@ fn test_save_dataframe_error_variant() {
         PoliteError::Connect { .. } | PoliteError::Save { .. }
     ));
 }
+
+#[test]
+fn test_conversion_error_details() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.execute_batch("CREATE TABLE t (n INTEGER); INSERT INTO t VALUES (1), ('abc');")
+        .unwrap();
+
+    let err = to_dataframe(db_path, "SELECT n FROM t").unwrap_err();
+    let PoliteError::Conversion(e) = &err else {
+        panic!("expected a conversion error, got {err:?}");
+    };
+    assert_eq!(e.db_path, db_path);
+    assert_eq!(e.query, "SELECT n FROM t");
+    assert_eq!((e.column.as_str(), e.row), ("n", 1));
+    assert_eq!(e.value, "'abc'");
+    assert_eq!(e.decl_type.as_deref(), Some("INTEGER"));
+    assert_eq!((e.expected.as_str(), e.actual.as_str()), ("i64", "text"));
+    assert!(!err.is_retryable());
+}
+
+#[test]
+fn test_save_error_reports_db_path_and_row() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let df = df! { "big" => [1u64, u64::MAX] }.unwrap();
+    let err = from_dataframe(&conn, "t", &df).unwrap_err();
+    let PoliteError::Save {
+        db_path: path,
+        source,
+        ..
+    } = &err
+    else {
+        panic!("expected a save error, got {err:?}");
+    };
+    assert_eq!(path, db_path);
+    let source = source.downcast_ref::<PoliteError>().unwrap();
+    assert!(
+        matches!(source, PoliteError::Conversion(e) if e.column == "big" && e.row == 1),
+        "{source:?}"
+    );
+}
+
+#[test]
+fn test_busy_is_retryable() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let holder = connect_sqlite(Some(db_path)).unwrap();
+    holder.execute_batch("BEGIN EXCLUSIVE").unwrap();
+
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.busy_timeout(std::time::Duration::ZERO).unwrap();
+    let df = df! { "id" => [1] }.unwrap();
+    let err = from_dataframe(&conn, "t", &df).unwrap_err();
+    assert_eq!(err.sqlite_code(), Some(rusqlite::ffi::SQLITE_BUSY));
+    assert!(err.is_retryable(), "{err:?}");
+}
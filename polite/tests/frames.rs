@@ -0,0 +1,122 @@
+// polite/tests/frames.rs
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::{query_frames, register_frame};
+use tempfile::NamedTempFile;
+
+fn users() -> DataFrame {
+    df! {
+        "id" => [1_i64, 2, 3],
+        "name" => [Some("Alice"), Some("Bob"), None],
+        "score" => [1.5, 2.5, 3.5],
+    }
+    .unwrap()
+}
+
+#[test]
+fn test_read_joins_a_frame_against_a_table() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE orders (user_id INTEGER, total REAL);
+         INSERT INTO orders VALUES (2, 5.0), (2, 7.5), (1, 3.0), (9, 1.0);",
+    )
+    .unwrap();
+
+    let options = ReadOptions {
+        connect: ConnectOptions::default().frame("users", users()),
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(
+        db_path,
+        "SELECT u.name, sum(o.total) AS spent FROM orders o
+         JOIN users u ON u.id = o.user_id GROUP BY u.name ORDER BY u.name",
+        &options,
+    )
+    .unwrap();
+
+    let names: Vec<_> = df
+        .column("name")
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(names, [Some("Alice"), Some("Bob")]);
+    let spent: Vec<_> = df
+        .column("spent")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(spent, [Some(3.0), Some(12.5)]);
+}
+
+#[test]
+fn test_frame_columns_keep_their_types() {
+    let df = query_frames("SELECT * FROM users WHERE id = 3", &[("users", &users())]).unwrap();
+    assert_eq!(df.height(), 1);
+    assert_eq!(df.column("id").unwrap().dtype(), &DataType::Int64);
+    assert_eq!(df.column("name").unwrap().dtype(), &DataType::String);
+    assert_eq!(df.column("score").unwrap().dtype(), &DataType::Float64);
+    assert_eq!(df.column("name").unwrap().null_count(), 1);
+    assert_eq!(df.column("score").unwrap().f64().unwrap().get(0), Some(3.5));
+}
+
+#[test]
+fn test_query_frames_joins_frames() {
+    let orders = df! { "user_id" => [2_i64, 2, 1], "total" => [5.0, 7.5, 3.0] }.unwrap();
+    let df = query_frames(
+        "SELECT name, count(*) AS n FROM orders JOIN users ON id = user_id
+         WHERE name = 'Bob' GROUP BY name",
+        &[("users", &users()), ("orders", &orders)],
+    )
+    .unwrap();
+    assert_eq!(df.height(), 1);
+    assert_eq!(df.column("n").unwrap().i64().unwrap().get(0), Some(2));
+}
+
+#[test]
+fn test_register_frame_on_a_connection() {
+    let conn = connect_sqlite(None).unwrap();
+    register_frame(&conn, "users", &users()).unwrap();
+
+    let name: String = conn
+        .query_row("SELECT name FROM users WHERE id = '2'", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(name, "Bob");
+
+    // Registering again replaces the frame
+    let one = df! { "id" => [7_i64] }.unwrap();
+    register_frame(&conn, "users", &one).unwrap();
+    let ids: i64 = conn
+        .query_row("SELECT sum(id) FROM users", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(ids, 7);
+    assert!(conn.execute("DELETE FROM users", []).is_err());
+}
+
+#[test]
+fn test_equality_under_another_collation_scans() {
+    let conn = connect_sqlite(None).unwrap();
+    register_frame(&conn, "users", &users()).unwrap();
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+
+    assert_eq!(count("SELECT count(*) FROM users WHERE name = 'bob'"), 0);
+    assert_eq!(
+        count("SELECT count(*) FROM users WHERE name = 'bob' COLLATE NOCASE"),
+        1
+    );
+    assert_eq!(
+        count("SELECT count(*) FROM users WHERE name = 'BOB ' COLLATE RTRIM"),
+        0
+    );
+    assert_eq!(
+        count("SELECT count(*) FROM users WHERE name = 'Bob  ' COLLATE RTRIM"),
+        1
+    );
+}
@@ -0,0 +1,118 @@
+// polite/tests/functions.rs
+use polite::functions::{Aggregate, Context, FunctionFlags, FunctionRegistry, WindowAggregate};
+use polite::prelude::*;
+use tempfile::NamedTempFile;
+
+/// Sum of squares, usable as an aggregate or window function
+#[derive(Clone)]
+struct SumSquares;
+
+impl Aggregate<i64, i64> for SumSquares {
+    fn init(&self, _: &mut Context<'_>) -> rusqlite::Result<i64> {
+        Ok(0)
+    }
+
+    fn step(&self, ctx: &mut Context<'_>, acc: &mut i64) -> rusqlite::Result<()> {
+        let x: i64 = ctx.get(0)?;
+        *acc += x * x;
+        Ok(())
+    }
+
+    fn finalize(&self, _: &mut Context<'_>, acc: Option<i64>) -> rusqlite::Result<i64> {
+        Ok(acc.unwrap_or(0))
+    }
+}
+
+impl WindowAggregate<i64, i64> for SumSquares {
+    fn value(&self, acc: Option<&mut i64>) -> rusqlite::Result<i64> {
+        Ok(acc.map_or(0, |acc| *acc))
+    }
+
+    fn inverse(&self, ctx: &mut Context<'_>, acc: &mut i64) -> rusqlite::Result<()> {
+        let x: i64 = ctx.get(0)?;
+        *acc -= x * x;
+        Ok(())
+    }
+}
+
+fn registry() -> FunctionRegistry {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    FunctionRegistry::new()
+        .scalar("slugify", 1, flags, |ctx| {
+            Ok(ctx.get::<String>(0)?.to_lowercase().replace(' ', "-"))
+        })
+        .aggregate("sum_squares", 1, flags, SumSquares)
+        .window("running_squares", 1, flags, SumSquares)
+}
+
+fn db() -> (NamedTempFile, ConnectOptions) {
+    let tmp = NamedTempFile::new().unwrap();
+    let connect = ConnectOptions::default().functions(registry());
+    let conn = connect_sqlite_with_options(tmp.path().to_str(), &connect).unwrap();
+    execute_query(&conn, "CREATE TABLE t (id INTEGER, title TEXT, slug TEXT)").unwrap();
+    // The functions work in statements on connections polite opens
+    execute_query(
+        &conn,
+        "INSERT INTO t SELECT value, 'Post Number ' || value, slugify('Post Number ' || value) \
+         FROM (SELECT 1 AS value UNION ALL SELECT 2 UNION ALL SELECT 3)",
+    )
+    .unwrap();
+    (tmp, connect)
+}
+
+#[test]
+fn test_scalar_function_in_read() {
+    let (tmp, connect) = db();
+    let opts = ReadOptions {
+        connect,
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(
+        tmp.path().to_str().unwrap(),
+        "SELECT slug, slugify(title) AS again FROM t ORDER BY id",
+        &opts,
+    )
+    .unwrap();
+    let slug = df.column("slug").unwrap().str().unwrap();
+    let again = df.column("again").unwrap().str().unwrap();
+    assert_eq!(slug.get(0), Some("post-number-1"));
+    assert_eq!(again.get(2), Some("post-number-3"));
+}
+
+#[test]
+fn test_aggregate_and_window_functions_in_read() {
+    let (tmp, connect) = db();
+    let opts = ReadOptions {
+        connect,
+        ..Default::default()
+    };
+    let db_path = tmp.path().to_str().unwrap();
+
+    let df =
+        to_dataframe_with_options(db_path, "SELECT sum_squares(id) AS s FROM t", &opts).unwrap();
+    assert_eq!(df.column("s").unwrap().i64().unwrap().get(0), Some(14));
+
+    let df = to_dataframe_with_options(
+        db_path,
+        "SELECT running_squares(id) OVER (ORDER BY id ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) \
+         AS r FROM t ORDER BY id",
+        &opts,
+    )
+    .unwrap();
+    let r: Vec<i64> = df
+        .column("r")
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(r, [1, 5, 13]);
+}
+
+#[test]
+fn test_unregistered_function_fails() {
+    let (tmp, _) = db();
+    let err =
+        to_dataframe(tmp.path().to_str().unwrap(), "SELECT slugify(title) FROM t").unwrap_err();
+    assert!(matches!(err, PoliteError::Query { .. }));
+}
@@ -0,0 +1,234 @@
+// polite/tests/incremental.rs
+mod common;
+
+use polars::prelude::*;
+use polite::incremental::{TableWatermarks, WatermarkStore, WATERMARK_TABLE};
+use polite::prelude::*;
+use polite::{read_incremental, read_incremental_with_options, IncrementalOptions, WatermarkSpec};
+use rusqlite::types::Value;
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+
+fn events() -> NamedTempFile {
+    common::db(
+        "CREATE TABLE events (id INTEGER PRIMARY KEY, kind TEXT, seq INTEGER, updated_at TEXT);
+         INSERT INTO events (kind, seq, updated_at) VALUES
+             ('open', 10, '2024-01-01 09:00'), ('close', 20, '2024-01-01 10:00');",
+    )
+}
+
+fn kinds(df: &DataFrame) -> Vec<Option<String>> {
+    common::strings(df, "kind")
+}
+
+#[test]
+fn test_rowid_watermark_persists_between_calls() {
+    let tmp = events();
+    let db_path = tmp.path().to_str().unwrap();
+
+    let first = read_incremental(db_path, "events", &WatermarkSpec::rowid()).unwrap();
+    assert_eq!(
+        kinds(&first.df),
+        [Some("open".into()), Some("close".into())]
+    );
+    assert_eq!(first.watermark, Some(Value::Integer(2)));
+
+    // Nothing new: no rows, and the watermark is unchanged
+    let again = read_incremental(db_path, "events", &WatermarkSpec::rowid()).unwrap();
+    assert_eq!(again.df.height(), 0);
+    assert_eq!(again.df.get_column_names(), first.df.get_column_names());
+    assert_eq!(again.watermark, Some(Value::Integer(2)));
+
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.execute_batch("INSERT INTO events (kind) VALUES ('reopen')")
+        .unwrap();
+    let next = read_incremental(db_path, "events", &WatermarkSpec::rowid()).unwrap();
+    assert_eq!(kinds(&next.df), [Some("reopen".into())]);
+    assert_eq!(
+        TableWatermarks::new(db_path)
+            .load("events", "rowid")
+            .unwrap(),
+        Some(Value::Integer(3))
+    );
+}
+
+#[test]
+fn test_monotonic_column_without_store() {
+    let tmp = events();
+    let db_path = tmp.path().to_str().unwrap();
+    let options = IncrementalOptions {
+        after: Some(Value::Integer(10)),
+        ..Default::default()
+    };
+    let read = read_incremental_with_options(
+        db_path,
+        "events",
+        &WatermarkSpec::monotonic("seq"),
+        &options,
+    )
+    .unwrap();
+    assert_eq!(kinds(&read.df), [Some("close".into())]);
+    assert_eq!(read.watermark, Some(Value::Integer(20)));
+
+    // Nothing was persisted
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    assert!(list_tables_named(&conn, WATERMARK_TABLE).is_empty());
+}
+
+fn list_tables_named(conn: &rusqlite::Connection, name: &str) -> Vec<String> {
+    let tables = polite::list_tables(conn).unwrap();
+    tables
+        .column("name")
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .filter(|t| *t == name)
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_updated_at_rereads_changed_rows() {
+    let tmp = events();
+    let db_path = tmp.path().to_str().unwrap();
+    let spec = WatermarkSpec::updated_at("updated_at");
+
+    let first = read_incremental(db_path, "events", &spec).unwrap();
+    assert_eq!(first.df.height(), 2);
+    assert_eq!(
+        first.watermark,
+        Some(Value::Text("2024-01-01 10:00".into()))
+    );
+
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.execute_batch(
+        "UPDATE events SET kind = 'opened', updated_at = '2024-01-02 08:00' WHERE id = 1",
+    )
+    .unwrap();
+    let changed = read_incremental(db_path, "events", &spec).unwrap();
+    // The row stamped at the old watermark is read again along with the changed one
+    assert_eq!(
+        kinds(&changed.df),
+        [Some("close".into()), Some("opened".into())]
+    );
+    assert_eq!(
+        changed.watermark,
+        Some(Value::Text("2024-01-02 08:00".into()))
+    );
+}
+
+#[test]
+fn test_updated_at_without_new_writes_reads_nothing() {
+    let tmp = events();
+    let db_path = tmp.path().to_str().unwrap();
+    let spec = WatermarkSpec::updated_at("updated_at");
+
+    assert_eq!(
+        read_incremental(db_path, "events", &spec)
+            .unwrap()
+            .df
+            .height(),
+        2
+    );
+    let again = read_incremental(db_path, "events", &spec).unwrap();
+    assert_eq!(again.df.height(), 0);
+    assert_eq!(again.df.width(), 4);
+    assert_eq!(
+        again.watermark,
+        Some(Value::Text("2024-01-01 10:00".into()))
+    );
+
+    // A write sharing the last stamp is picked up with the next newer one
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.execute_batch(
+        "UPDATE events SET kind = 'closed' WHERE id = 2;
+         INSERT INTO events (kind, seq, updated_at) VALUES ('open', 30, '2024-01-02 08:00');",
+    )
+    .unwrap();
+    let next = read_incremental(db_path, "events", &spec).unwrap();
+    assert_eq!(
+        kinds(&next.df),
+        [Some("closed".into()), Some("open".into())]
+    );
+}
+
+#[derive(Default)]
+struct Memory(std::sync::Mutex<Option<Value>>);
+
+impl WatermarkStore for Memory {
+    fn load(&self, _table: &str, _column: &str) -> Result<Option<Value>, PoliteError> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    fn save(&self, _table: &str, _column: &str, watermark: &Value) -> Result<(), PoliteError> {
+        *self.0.lock().unwrap() = Some(watermark.clone());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_caller_store_and_empty_table() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.execute_batch("CREATE TABLE t (id INTEGER PRIMARY KEY, v REAL)")
+        .unwrap();
+
+    let store = Arc::new(Memory::default());
+    let options = IncrementalOptions {
+        store: Some(store.clone()),
+        ..Default::default()
+    };
+    let spec = WatermarkSpec::monotonic("id");
+    let empty = read_incremental_with_options(db_path, "t", &spec, &options).unwrap();
+    assert_eq!(empty.df.height(), 0);
+    assert_eq!(empty.df.get_column_names(), ["id", "v"]);
+    assert_eq!(empty.watermark, None);
+
+    conn.execute_batch("INSERT INTO t VALUES (5, 1.5)").unwrap();
+    let read = read_incremental_with_options(db_path, "t", &spec, &options).unwrap();
+    assert_eq!(read.df.height(), 1);
+    assert_eq!(*store.0.lock().unwrap(), Some(Value::Integer(5)));
+}
+
+#[test]
+fn test_rowid_watermark_must_be_integer() {
+    let tmp = events();
+    let spec = WatermarkSpec {
+        column: "kind".into(),
+        kind: polite::WatermarkKind::RowId,
+    };
+    let err = read_incremental(tmp.path().to_str().unwrap(), "events", &spec).unwrap_err();
+    assert!(matches!(err, PoliteError::Watermark { .. }), "{err}");
+}
+
+#[test]
+fn test_table_watermarks_open_with_connect_options() {
+    let tmp = events();
+    let db_path = tmp.path().to_str().unwrap();
+    let read = ReadOptions {
+        connect: ConnectOptions::default().read_only(true),
+        ..Default::default()
+    };
+    let options = IncrementalOptions {
+        store: Some(Arc::new(TableWatermarks::with_options(
+            db_path,
+            &read.connect,
+        ))),
+        read,
+        ..Default::default()
+    };
+
+    // The rows are read, but the store opens the database read-only too, so the save fails
+    let err = read_incremental_with_options(db_path, "events", &WatermarkSpec::rowid(), &options)
+        .unwrap_err();
+    assert!(matches!(err, PoliteError::Exec { .. }), "{err}");
+    assert_eq!(
+        TableWatermarks::new(db_path)
+            .load("events", "rowid")
+            .unwrap(),
+        None
+    );
+}
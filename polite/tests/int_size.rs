@@ -94,3 +94,128 @@ fn test_large_integer_promotes_to_i64_with_small_after() {
     assert!(values.contains(&BIG));
     assert!(values.contains(&42));
 }
+
+// Narrower declared types are honoured rather than widened to Int64
+
+#[test]
+fn test_declared_widths_are_preserved() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute(
+        "CREATE TABLE t (a TINYINT, b SMALLINT, c INT4, d BIGINT, e TINYINT UNSIGNED, \
+         f SMALLINT UNSIGNED, g INT UNSIGNED, h UNSIGNED BIG INT)",
+        [],
+    )
+    .unwrap();
+    conn.execute("INSERT INTO t VALUES (-1, -2, -3, -4, 5, 6, 7, 8)", [])
+        .unwrap();
+
+    let df = to_dataframe(db_path, "SELECT * FROM t").unwrap();
+    let dtypes: Vec<DataType> = df.dtypes();
+    assert_eq!(
+        dtypes,
+        vec![
+            DataType::Int8,
+            DataType::Int16,
+            DataType::Int32,
+            DataType::Int64,
+            DataType::UInt8,
+            DataType::UInt16,
+            DataType::UInt32,
+            DataType::UInt64,
+        ]
+    );
+    assert_eq!(df.column("a").unwrap().i8().unwrap().get(0), Some(-1));
+    assert_eq!(df.column("h").unwrap().u64().unwrap().get(0), Some(8));
+
+    // The same mapping applies when there are no rows to look at
+    let empty = to_dataframe(db_path, "SELECT * FROM t WHERE 0").unwrap();
+    assert_eq!(empty.dtypes(), dtypes);
+}
+
+#[test]
+fn test_uint_bit_width_decl_types() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute("CREATE TABLE t (a UINT8, b UINT16, c UINT32, d UINT64)", [])
+        .unwrap();
+    conn.execute("INSERT INTO t VALUES (255, 65535, 1, 2)", [])
+        .unwrap();
+
+    let df = to_dataframe(db_path, "SELECT * FROM t").unwrap();
+    assert_eq!(
+        df.dtypes(),
+        vec![
+            DataType::UInt8,
+            DataType::UInt16,
+            DataType::UInt32,
+            DataType::UInt64
+        ]
+    );
+    assert_eq!(df.column("a").unwrap().u8().unwrap().get(0), Some(255));
+}
+
+#[test]
+fn test_out_of_range_value_reports_row() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute("CREATE TABLE t (id TINYINT)", []).unwrap();
+    conn.execute("INSERT INTO t VALUES (1), (2), (300)", [])
+        .unwrap();
+
+    let err = to_dataframe(db_path, "SELECT * FROM t").unwrap_err();
+    let msg = err.to_string();
+    assert!(
+        matches!(&err, PoliteError::Conversion(e)
+            if e.column == "id" && e.row == 2 && e.value == "300"),
+        "{msg}"
+    );
+    assert!(msg.contains("'id' at row 2"), "{msg}");
+}
+
+#[test]
+fn test_widen_integers_option() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute("CREATE TABLE t (a TINYINT, b SMALLINT UNSIGNED)", [])
+        .unwrap();
+    conn.execute("INSERT INTO t VALUES (1, 2)", []).unwrap();
+
+    let opts = ReadOptions {
+        widen_integers: true,
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(db_path, "SELECT * FROM t", &opts).unwrap();
+    assert_eq!(df.dtypes(), vec![DataType::Int64, DataType::Int64]);
+}
+
+#[test]
+fn test_narrow_integers_roundtrip() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let df = df! {
+        "a" => [1i8, -2],
+        "b" => [3i16, -4],
+        "c" => [5i32, -6],
+        "d" => [7u8, 8],
+        "e" => [9u16, 10],
+        "f" => [11u32, 12],
+        "g" => [13u64, 14],
+    }
+    .unwrap();
+    from_dataframe(&conn, "t", &df).unwrap();
+
+    let df2 = to_dataframe(db_path, "SELECT * FROM t").unwrap();
+    assert_eq!(df2.dtypes(), df.dtypes());
+    assert!(df2.equals(&df));
+}
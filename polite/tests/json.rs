@@ -0,0 +1,105 @@
+// polite/tests/json.rs
+use polars::prelude::*;
+use polite::prelude::*;
+use tempfile::NamedTempFile;
+
+#[test]
+fn test_json_decl_reads_as_struct() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch(
+        r#"CREATE TABLE t (doc JSON);
+           INSERT INTO t VALUES ('{"a": 1, "b": "x"}');
+           INSERT INTO t VALUES ('{"a": 2, "b": "y", "c": true}');
+           INSERT INTO t VALUES (NULL);"#,
+    )
+    .unwrap();
+
+    let df = to_dataframe(db_path, "SELECT doc FROM t").unwrap();
+    let DataType::Struct(fields) = df.column("doc").unwrap().dtype() else {
+        panic!(
+            "expected a struct, got {:?}",
+            df.column("doc").unwrap().dtype()
+        );
+    };
+    let names: Vec<&str> = fields.iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+
+    let a = df
+        .column("doc")
+        .unwrap()
+        .struct_()
+        .unwrap()
+        .field_by_name("a")
+        .unwrap();
+    assert_eq!(a.dtype(), &DataType::Int64);
+    assert_eq!(a.i64().unwrap().get(1), Some(2));
+    assert_eq!(df.column("doc").unwrap().null_count(), 1);
+}
+
+#[test]
+fn test_json_columns_option_reads_text_as_list() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch(
+        "CREATE TABLE t (tags TEXT, raw TEXT);
+         INSERT INTO t VALUES ('[1, 2, 3]', '[1]');
+         INSERT INTO t VALUES ('[]', '[2]');",
+    )
+    .unwrap();
+
+    let options = ReadOptions {
+        json_columns: vec!["tags".to_string()],
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(db_path, "SELECT tags, raw FROM t", &options).unwrap();
+
+    assert_eq!(
+        df.column("tags").unwrap().dtype(),
+        &DataType::List(Box::new(DataType::Int64))
+    );
+    // Columns not listed are left as text
+    assert_eq!(df.column("raw").unwrap().dtype(), &DataType::String);
+}
+
+#[test]
+fn test_nested_columns_roundtrip_as_json() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let df = df! {
+        "id" => [1i64, 2],
+        "a" => [10i64, 20],
+        "b" => ["x", "y"],
+    }
+    .unwrap()
+    .lazy()
+    .select([
+        col("id"),
+        as_struct(vec![col("a"), col("b")]).alias("doc"),
+        concat_list([col("a"), col("id")]).unwrap().alias("nums"),
+    ])
+    .collect()
+    .unwrap();
+
+    from_dataframe(&conn, "t", &df).unwrap();
+
+    // Stored as JSON text that SQLite's JSON1 functions understand
+    let (valid, b): (bool, String) = conn
+        .query_row(
+            "SELECT json_valid(doc) AND json_valid(nums), doc ->> '$.b' FROM t WHERE id = 2",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert!(valid);
+    assert_eq!(b, "y");
+
+    let back = to_dataframe(db_path, "SELECT * FROM t").unwrap();
+    assert!(back.equals_missing(&df), "{back:?} != {df:?}");
+}
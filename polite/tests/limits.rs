@@ -0,0 +1,102 @@
+// polite/tests/limits.rs
+mod common;
+
+use polite::prelude::*;
+use polite::{to_dataframe_limited, OnLimit, ReadLimit};
+use tempfile::NamedTempFile;
+
+fn db_with_rows(n: i64) -> NamedTempFile {
+    common::db(&format!(
+        "CREATE TABLE t AS WITH RECURSIVE n(id) AS \
+         (SELECT 0 UNION ALL SELECT id + 1 FROM n WHERE id + 1 < {n}) \
+         SELECT id, 'name ' || id AS name FROM n"
+    ))
+}
+
+#[test]
+fn test_max_rows_error() {
+    let tmp = db_with_rows(100);
+    let db_path = tmp.path().to_str().unwrap();
+    let opts = ReadOptions {
+        max_rows: Some(10),
+        ..Default::default()
+    };
+
+    let err = to_dataframe_with_options(db_path, "SELECT * FROM t", &opts).unwrap_err();
+    assert!(matches!(
+        err,
+        PoliteError::LimitExceeded {
+            limit: ReadLimit::Rows(10),
+            ..
+        }
+    ));
+    assert!(err.to_string().contains("more than 10 row(s)"));
+}
+
+#[test]
+fn test_max_rows_truncate() {
+    let tmp = db_with_rows(100);
+    let db_path = tmp.path().to_str().unwrap();
+    let opts = ReadOptions {
+        max_rows: Some(10),
+        on_limit: OnLimit::Truncate,
+        ..Default::default()
+    };
+
+    let read = to_dataframe_limited(db_path, "SELECT * FROM t ORDER BY id", &opts).unwrap();
+    assert!(read.truncated);
+    assert_eq!(read.df.height(), 10);
+    let ids: Vec<i64> = read
+        .df
+        .column("id")
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(ids, (0..10).collect::<Vec<_>>());
+
+    let df = to_dataframe_with_options(db_path, "SELECT * FROM t", &opts).unwrap();
+    assert_eq!(df.height(), 10);
+}
+
+#[test]
+fn test_result_within_max_rows_is_not_truncated() {
+    let tmp = db_with_rows(10);
+    let db_path = tmp.path().to_str().unwrap();
+    let opts = ReadOptions {
+        max_rows: Some(10),
+        ..Default::default()
+    };
+
+    let read = to_dataframe_limited(db_path, "SELECT * FROM t", &opts).unwrap();
+    assert!(!read.truncated);
+    assert_eq!(read.df.height(), 10);
+}
+
+#[test]
+fn test_max_bytes() {
+    let tmp = db_with_rows(50_000);
+    let db_path = tmp.path().to_str().unwrap();
+    let opts = ReadOptions {
+        max_bytes: Some(100_000),
+        ..Default::default()
+    };
+
+    let err = to_dataframe_with_options(db_path, "SELECT * FROM t", &opts).unwrap_err();
+    assert!(matches!(
+        err,
+        PoliteError::LimitExceeded {
+            limit: ReadLimit::Bytes(100_000),
+            ..
+        }
+    ));
+
+    let opts = ReadOptions {
+        on_limit: OnLimit::Truncate,
+        ..opts
+    };
+    let read = to_dataframe_limited(db_path, "SELECT * FROM t", &opts).unwrap();
+    assert!(read.truncated);
+    assert!(read.df.height() > 0 && read.df.height() < 50_000);
+}
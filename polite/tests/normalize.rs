@@ -0,0 +1,145 @@
+// polite/tests/normalize.rs
+use polars::prelude::*;
+use polite::prelude::*;
+use tempfile::NamedTempFile;
+
+fn normalized() -> WriteOptions {
+    WriteOptions {
+        normalize_categoricals: true,
+        ..Default::default()
+    }
+}
+
+fn status_frame(values: &[&str]) -> DataFrame {
+    df! { "id" => (0..values.len() as i64).collect::<Vec<_>>(), "status" => values }
+        .unwrap()
+        .lazy()
+        .with_column(col("status").cast(DataType::Categorical(None, Default::default())))
+        .collect()
+        .unwrap()
+}
+
+#[test]
+fn test_categorical_written_as_codes() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let df = status_frame(&["open", "closed", "open"]);
+    from_dataframe_with_options(&conn, "tickets", &df, &normalized()).unwrap();
+
+    let lookup: Vec<(i64, String)> = conn
+        .prepare("SELECT id, value FROM tickets__status ORDER BY id")
+        .unwrap()
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(lookup, [(1, "open".to_string()), (2, "closed".to_string())]);
+
+    let codes = to_dataframe(db_path, "SELECT status FROM tickets").unwrap();
+    let codes: Vec<_> = codes
+        .column("status")
+        .unwrap()
+        .i64()
+        .unwrap()
+        .iter()
+        .collect();
+    assert_eq!(codes, [Some(1), Some(2), Some(1)]);
+
+    let fk: String = conn
+        .query_row(
+            "SELECT \"table\" FROM pragma_foreign_key_list('tickets')",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(fk, "tickets__status");
+}
+
+#[test]
+fn test_append_merges_new_categories() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    from_dataframe_with_options(&conn, "t", &status_frame(&["a", "b"]), &normalized()).unwrap();
+    from_dataframe_with_options(&conn, "t", &status_frame(&["b", "c"]), &normalized()).unwrap();
+
+    let n: i64 = conn
+        .query_row("SELECT count(*) FROM t__status", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(n, 3);
+
+    let df = read_normalized(db_path, "t", &ReadOptions::default()).unwrap();
+    assert!(matches!(
+        df.column("status").unwrap().dtype(),
+        DataType::Categorical(_, _)
+    ));
+    let status = df
+        .column("status")
+        .unwrap()
+        .cast(&DataType::String)
+        .unwrap();
+    let status: Vec<_> = status.str().unwrap().iter().collect();
+    assert_eq!(status, [Some("a"), Some("b"), Some("b"), Some("c")]);
+}
+
+#[test]
+fn test_enum_roundtrip_keeps_categories() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    let levels = ["low", "medium", "high"];
+    let dtype = create_enum_dtype(polars_arrow::array::Utf8ViewArray::from_slice_values(
+        levels,
+    ));
+    let df = df! { "level" => [Some("high"), None, Some("low")] }
+        .unwrap()
+        .lazy()
+        .with_column(col("level").cast(dtype.clone()))
+        .collect()
+        .unwrap();
+
+    from_dataframe_with_options(&conn, "t", &df, &normalized()).unwrap();
+
+    let back = read_normalized(db_path, "t", &ReadOptions::default()).unwrap();
+    assert_eq!(back.column("level").unwrap().dtype(), &dtype);
+    assert!(back.equals_missing(&df), "{back:?} != {df:?}");
+}
+
+#[test]
+fn test_categorical_without_normalization_is_text() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    from_dataframe(&conn, "t", &status_frame(&["x", "y"])).unwrap();
+
+    let df = to_dataframe(db_path, "SELECT status FROM t").unwrap();
+    let status: Vec<_> = df.column("status").unwrap().str().unwrap().iter().collect();
+    assert_eq!(status, [Some("x"), Some("y")]);
+}
+
+#[test]
+fn test_read_normalized_quotes_names() {
+    let db = NamedTempFile::new().unwrap();
+    let db_path = db.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+
+    conn.execute_batch(
+        "CREATE TABLE \"line items__order\" (id INTEGER PRIMARY KEY, value TEXT UNIQUE);
+         CREATE TABLE \"line items\" (
+             \"order\" CATEGORY_ID REFERENCES \"line items__order\"(id), qty INTEGER
+         );
+         INSERT INTO \"line items__order\" (value) VALUES ('rush'), ('standard');
+         INSERT INTO \"line items\" VALUES (2, 1), (1, 3);",
+    )
+    .unwrap();
+
+    let df = read_normalized(db_path, "line items", &ReadOptions::default()).unwrap();
+    let order = df.column("order").unwrap().cast(&DataType::String).unwrap();
+    let order: Vec<_> = order.str().unwrap().iter().collect();
+    assert_eq!(order, [Some("standard"), Some("rush")]);
+}
@@ -0,0 +1,120 @@
+// polite/tests/partition.rs
+mod common;
+
+use common::read_partitions;
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::{PartitionOn, PartitionStrategy};
+use tempfile::NamedTempFile;
+
+/// 1000 events, most of them with small ids, and a few without an id.
+fn events() -> NamedTempFile {
+    common::db(
+        "CREATE TABLE events (id INTEGER, at TEXT, score REAL, label TEXT);
+         WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000)
+         INSERT INTO events SELECT
+             CASE WHEN i % 100 = 0 THEN NULL WHEN i > 990 THEN i * 1000 ELSE i END,
+             datetime('2024-01-01', '+' || (i * i) || ' minutes'),
+             i / 7.0,
+             printf('label-%04d', i)
+         FROM n;",
+    )
+}
+
+fn read(tmp: &NamedTempFile, on: PartitionOn) -> (DataFrame, Vec<usize>) {
+    let options = ReadOptions {
+        partition_on: Some(on),
+        ..Default::default()
+    };
+    read_partitions(tmp, "SELECT * FROM events", options)
+}
+
+fn sorted_labels(df: &DataFrame) -> Vec<Option<String>> {
+    let mut labels = common::strings(df, "label");
+    labels.sort();
+    labels
+}
+
+#[test]
+fn test_every_row_in_one_partition() {
+    let tmp = events();
+    let all = to_dataframe(tmp.path().to_str().unwrap(), "SELECT * FROM events").unwrap();
+    for column in ["id", "at", "score", "label"] {
+        for strategy in [PartitionStrategy::EqualWidth, PartitionStrategy::quantile()] {
+            if column == "label" && strategy == PartitionStrategy::EqualWidth {
+                continue;
+            }
+            let (df, sizes) = read(&tmp, PartitionOn::new(column, 4).strategy(strategy));
+            assert_eq!(
+                sorted_labels(&df),
+                sorted_labels(&all),
+                "{column} {strategy:?}"
+            );
+            assert_eq!(sizes.len(), 4, "{column} {strategy:?}");
+        }
+    }
+}
+
+#[test]
+fn test_quantiles_balance_skewed_columns() {
+    let tmp = events();
+    let (_, sizes) = read(&tmp, PartitionOn::new("id", 4));
+    assert!(sizes[0] > 900, "equal width puts most ids first: {sizes:?}");
+
+    let (_, sizes) = read(
+        &tmp,
+        PartitionOn::new("id", 4).strategy(PartitionStrategy::quantile()),
+    );
+    assert!(sizes.iter().all(|&n| (200..=300).contains(&n)), "{sizes:?}");
+}
+
+#[test]
+fn test_sampled_quantiles_are_reproducible() {
+    let tmp = events();
+    // A sample smaller than the table, which a random one would take differently every time
+    let strategy = PartitionStrategy::Quantile { sample_rows: 50 };
+    let (_, first) = read(&tmp, PartitionOn::new("score", 4).strategy(strategy));
+    assert_eq!(first.len(), 4);
+    assert!(first.iter().all(|&n| (200..=300).contains(&n)), "{first:?}");
+    for _ in 0..5 {
+        let (_, sizes) = read(&tmp, PartitionOn::new("score", 4).strategy(strategy));
+        assert_eq!(sizes, first);
+    }
+}
+
+#[test]
+fn test_quantiles_from_stat4() {
+    let tmp = events();
+    let conn = connect_sqlite(tmp.path().to_str()).unwrap();
+    conn.execute_batch("CREATE INDEX events_at ON events (at); ANALYZE;")
+        .unwrap();
+
+    // No values are sampled, so the split comes from the index's statistics alone
+    let strategy = PartitionStrategy::Quantile { sample_rows: 0 };
+    let (df, sizes) = read(&tmp, PartitionOn::new("at", 4).strategy(strategy));
+    assert_eq!(df.height(), 1000);
+    assert_eq!(sizes.len(), 4);
+    assert!(sizes.iter().all(|&n| (150..=350).contains(&n)), "{sizes:?}");
+
+    let (_, sizes) = read(&tmp, PartitionOn::new("label", 4).strategy(strategy));
+    assert_eq!(sizes, [1000], "an unindexed column is not split");
+}
+
+#[test]
+fn test_equal_width_needs_numbers_or_dates() {
+    let tmp = events();
+    let options = ReadOptions {
+        partition_on: Some(PartitionOn::new("label", 4)),
+        ..Default::default()
+    };
+    let err = to_dataframe_with_options(
+        tmp.path().to_str().unwrap(),
+        "SELECT * FROM events",
+        &options,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, PoliteError::Partition { column, .. } if column == "label"),
+        "{err}"
+    );
+}
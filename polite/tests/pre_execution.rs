@@ -0,0 +1,92 @@
+// polite/tests/pre_execution.rs
+mod common;
+
+use polite::prelude::*;
+use tempfile::NamedTempFile;
+
+fn db_with_table() -> NamedTempFile {
+    common::db(
+        "CREATE TABLE t (id INTEGER, name TEXT);
+         INSERT INTO t VALUES (1, 'a'), (2, 'b'), (3, 'c');",
+    )
+}
+
+#[test]
+fn test_pragma_applies_to_read_connections() {
+    let tmp = db_with_table();
+    let db_path = tmp.path().to_str().unwrap();
+    let opts = ReadOptions {
+        pre_execution_queries: vec!["PRAGMA cache_size = -1234".to_string()],
+        ..Default::default()
+    };
+
+    let df = to_dataframe_with_options(db_path, "SELECT cache_size FROM pragma_cache_size", &opts)
+        .unwrap();
+    assert_eq!(
+        df.column("cache_size").unwrap().i64().unwrap().get(0),
+        Some(-1234)
+    );
+}
+
+#[test]
+fn test_temp_view_and_attach() {
+    let tmp = db_with_table();
+    let db_path = tmp.path().to_str().unwrap();
+    let other = db_with_table();
+    let other_path = other.path().to_str().unwrap();
+    let opts = ReadOptions {
+        pre_execution_queries: vec![
+            format!("ATTACH '{other_path}' AS other"),
+            "CREATE TEMP VIEW big AS SELECT id, name FROM t WHERE id > 1".to_string(),
+        ],
+        ..Default::default()
+    };
+
+    let df = to_dataframe_with_options(
+        db_path,
+        "SELECT big.id, o.name FROM big JOIN other.t AS o USING (id) ORDER BY id",
+        &opts,
+    )
+    .unwrap();
+    assert_eq!(df.height(), 2);
+}
+
+#[test]
+fn test_failing_statement_is_reported() {
+    let tmp = db_with_table();
+    let db_path = tmp.path().to_str().unwrap();
+    let opts = ReadOptions {
+        pre_execution_queries: vec!["PRAGMA nonsense = (".to_string()],
+        ..Default::default()
+    };
+
+    let err = to_dataframe_with_options(db_path, "SELECT * FROM t", &opts).unwrap_err();
+    match &err {
+        PoliteError::PreExecution { sql, .. } => assert_eq!(sql, "PRAGMA nonsense = ("),
+        other => panic!("expected PreExecution, got {other:?}"),
+    }
+    assert!(err.to_string().contains("PRAGMA nonsense = ("));
+}
+
+#[test]
+fn test_statement_failing_on_pool_is_reported() {
+    let tmp = db_with_table();
+    let db_path = tmp.path().to_str().unwrap();
+    // Succeeds on the preflight connection, then fails on the pooled one as the table exists
+    let opts = ReadOptions {
+        pre_execution_queries: vec!["CREATE TABLE side (x)".to_string()],
+        ..Default::default()
+    };
+
+    let start = std::time::Instant::now();
+    let err = to_dataframe_with_options(db_path, "SELECT * FROM t", &opts).unwrap_err();
+    match &err {
+        PoliteError::PreExecution { sql, source, .. } => {
+            assert_eq!(sql, "CREATE TABLE side (x)");
+            assert!(source.to_string().contains("already exists"), "{source}");
+        }
+        other => panic!("expected PreExecution, got {other:?}"),
+    }
+    // Not after waiting out the pool's connection timeout
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+}
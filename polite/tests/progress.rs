@@ -0,0 +1,125 @@
+// polite/tests/progress.rs
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::progress::{Phase, ProgressObserver, PROGRESS_CHUNK};
+use std::sync::{Arc, Mutex};
+use tempfile::NamedTempFile;
+
+#[derive(Default)]
+struct Recorder {
+    phases: Mutex<Vec<Phase>>,
+    fetched: Mutex<Vec<(usize, usize, Option<usize>)>>,
+    written: Mutex<Vec<(String, usize, usize)>>,
+    totals: bool,
+}
+
+impl ProgressObserver for Recorder {
+    fn phase(&self, phase: Phase) {
+        self.phases.lock().unwrap().push(phase);
+    }
+
+    fn rows_fetched(&self, partition: usize, rows: usize, total: Option<usize>) {
+        self.fetched.lock().unwrap().push((partition, rows, total));
+    }
+
+    fn rows_written(&self, table: &str, rows: usize, total: usize) {
+        self.written
+            .lock()
+            .unwrap()
+            .push((table.to_string(), rows, total));
+    }
+
+    fn wants_totals(&self) -> bool {
+        self.totals
+    }
+}
+
+fn counting() -> Arc<Recorder> {
+    Arc::new(Recorder {
+        totals: true,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_write_and_read_progress() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    let n = 2500;
+    let df = df! { "id" => (0..n as i64).collect::<Vec<_>>() }.unwrap();
+
+    let recorder = counting();
+    let write_opts = WriteOptions {
+        progress: Some(recorder.clone()),
+        ..Default::default()
+    };
+    from_dataframe_with_options(&conn, "t", &df, &write_opts).unwrap();
+    assert_eq!(
+        *recorder.written.lock().unwrap(),
+        [
+            ("t".to_string(), PROGRESS_CHUNK, n),
+            ("t".to_string(), 2 * PROGRESS_CHUNK, n),
+            ("t".to_string(), n, n),
+        ]
+    );
+
+    let read_opts = ReadOptions {
+        progress: Some(recorder.clone()),
+        ..Default::default()
+    };
+    let out = to_dataframe_with_options(db_path, "SELECT id FROM t", &read_opts).unwrap();
+    assert_eq!(out.height(), n);
+    assert_eq!(
+        *recorder.phases.lock().unwrap(),
+        [
+            Phase::Preflight,
+            Phase::Metadata,
+            Phase::Counting,
+            Phase::Fetching,
+            Phase::Converting,
+        ]
+    );
+    assert_eq!(
+        *recorder.fetched.lock().unwrap(),
+        [
+            (0, PROGRESS_CHUNK, Some(n)),
+            (0, 2 * PROGRESS_CHUNK, Some(n)),
+            (0, n, Some(n)),
+        ]
+    );
+}
+
+#[test]
+fn test_empty_read_reports_final_count() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    execute_query(&conn, "CREATE TABLE t (id INTEGER)").unwrap();
+
+    let recorder = counting();
+    let opts = ReadOptions {
+        progress: Some(recorder.clone()),
+        ..Default::default()
+    };
+    to_dataframe_with_options(db_path, "SELECT id FROM t", &opts).unwrap();
+    assert_eq!(*recorder.fetched.lock().unwrap(), [(0, 0, Some(0))]);
+}
+
+#[test]
+fn test_totals_are_not_counted_by_default() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.execute_batch("CREATE TABLE t (id INTEGER); INSERT INTO t VALUES (1), (2)")
+        .unwrap();
+
+    let recorder = Arc::new(Recorder::default());
+    let opts = ReadOptions {
+        progress: Some(recorder.clone()),
+        ..Default::default()
+    };
+    to_dataframe_with_options(db_path, "SELECT id FROM t", &opts).unwrap();
+    assert!(!recorder.phases.lock().unwrap().contains(&Phase::Counting));
+    assert_eq!(*recorder.fetched.lock().unwrap(), [(0, 2, None)]);
+}
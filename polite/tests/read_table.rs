@@ -0,0 +1,163 @@
+// polite/tests/read_table.rs
+mod common;
+
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::table::{table_query, Filter, OrderBy, Sample};
+use polite::PartitionOn;
+use rusqlite::types::Value;
+use tempfile::NamedTempFile;
+
+fn people() -> NamedTempFile {
+    common::db(
+        "CREATE TABLE \"odd \"\"table\"\"\" (id INTEGER, \"first name\" TEXT, age INTEGER);
+         INSERT INTO \"odd \"\"table\"\"\" VALUES
+             (1, 'Alice', 30), (2, 'Bob', 17), (3, 'O''Brien', 45), (4, 'Dora', NULL);",
+    )
+}
+
+const TABLE: &str = "odd \"table\"";
+
+fn ids(df: &DataFrame) -> Vec<Option<i64>> {
+    df.column("id")
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn test_columns_filters_and_ordering() {
+    let tmp = people();
+    let df = read_table(
+        tmp.path().to_str().unwrap(),
+        TABLE,
+        &ReadTableOptions {
+            columns: vec!["id".into(), "first name".into()],
+            filter: vec![
+                Filter::gt_eq("age", 18),
+                Filter::not_eq("first name", "Bob".to_string()),
+            ],
+            order_by: vec![OrderBy::desc("age")],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(df.get_column_names(), ["id", "first name"]);
+    assert_eq!(ids(&df), [Some(3), Some(1)]);
+}
+
+#[test]
+fn test_values_are_bound_not_sql() {
+    let tmp = people();
+    let db_path = tmp.path().to_str().unwrap();
+    let options = ReadTableOptions {
+        filter: vec![Filter::eq("first name", "O'Brien".to_string())],
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(&read_table(db_path, TABLE, &options).unwrap()),
+        [Some(3)]
+    );
+
+    let injection = ReadTableOptions {
+        filter: vec![Filter::eq("first name", "x' OR '1'='1".to_string())],
+        ..Default::default()
+    };
+    assert_eq!(read_table(db_path, TABLE, &injection).unwrap().height(), 0);
+
+    // The values are kept out of the query's text
+    let (sql, params) = table_query(TABLE, &injection);
+    assert!(!sql.contains("OR"), "{sql}");
+    assert_eq!(params, [Value::Text("x' OR '1'='1".to_string())]);
+
+    let partitioned = ReadTableOptions {
+        filter: vec![Filter::is_in("id", [1_i64, 3, 4]), Filter::lt("age", 50.5)],
+        read: ReadOptions {
+            partition_on: Some(PartitionOn::new("id", 2)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut found = ids(&read_table(db_path, TABLE, &partitioned).unwrap());
+    found.sort();
+    assert_eq!(found, [Some(1), Some(3)]);
+}
+
+#[test]
+fn test_in_null_limit_and_offset() {
+    let tmp = people();
+    let db_path = tmp.path().to_str().unwrap();
+    let df = read_table(
+        db_path,
+        TABLE,
+        &ReadTableOptions {
+            filter: vec![Filter::is_in("id", [1_i64, 2, 4])],
+            order_by: vec![OrderBy::asc("id")],
+            limit: Some(1),
+            offset: Some(1),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&df), [Some(2)]);
+
+    let df = read_table(
+        db_path,
+        TABLE,
+        &ReadTableOptions {
+            filter: vec![Filter::is_null("age")],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(&df), [Some(4)]);
+
+    let options = ReadTableOptions {
+        order_by: vec![OrderBy::asc("id")],
+        offset: Some(3),
+        ..Default::default()
+    };
+    assert!(table_query(TABLE, &options)
+        .0
+        .ends_with("LIMIT -1 OFFSET 3"));
+    assert_eq!(
+        ids(&read_table(db_path, TABLE, &options).unwrap()),
+        [Some(4)]
+    );
+}
+
+#[test]
+fn test_sample_is_reproducible() {
+    let tmp = NamedTempFile::new().unwrap();
+    let db_path = tmp.path().to_str().unwrap();
+    let conn = connect_sqlite(Some(db_path)).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE t (id INTEGER);
+         WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000)
+         INSERT INTO t SELECT i FROM n;",
+    )
+    .unwrap();
+
+    let sample = |seed| {
+        let df = read_table(
+            db_path,
+            "t",
+            &ReadTableOptions {
+                filter: vec![Filter::gt("id", 500)],
+                order_by: vec![OrderBy::asc("id")],
+                sample: Some(Sample { rows: 20, seed }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        ids(&df)
+    };
+    let first = sample(7);
+    assert_eq!(first.len(), 20);
+    assert!(first.iter().all(|id| id.unwrap() > 500));
+    assert!(first.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(sample(7), first);
+    assert_ne!(sample(8), first);
+}
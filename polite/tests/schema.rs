@@ -0,0 +1,146 @@
+// polite/tests/schema.rs
+mod common;
+
+use common::strings;
+use polite::prelude::*;
+use polite::schema::{
+    foreign_keys, indexes, list_tables, list_views, table_schema, table_schema_with_options,
+    table_stats,
+};
+use tempfile::NamedTempFile;
+
+fn catalog() -> (NamedTempFile, rusqlite::Connection) {
+    let tmp = NamedTempFile::new().unwrap();
+    let conn = connect_sqlite(tmp.path().to_str()).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE users (
+             id INTEGER PRIMARY KEY,
+             name TEXT NOT NULL DEFAULT 'anon',
+             age SMALLINT,
+             balance DECIMAL(10,2),
+             email TEXT UNIQUE,
+             misc
+         );
+         CREATE TABLE orders (
+             id INTEGER PRIMARY KEY,
+             user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+             total REAL
+         );
+         CREATE INDEX orders_by_user ON orders (user_id, total);
+         CREATE VIEW big_orders AS SELECT * FROM orders WHERE total > 100;
+         INSERT INTO users (id, name) VALUES (1, 'Alice'), (2, 'Bob');
+         INSERT INTO orders VALUES (1, 1, 5.0);",
+    )
+    .unwrap();
+    (tmp, conn)
+}
+
+#[test]
+fn test_list_tables_and_views() {
+    let (_tmp, conn) = catalog();
+    let tables = list_tables(&conn).unwrap();
+    assert_eq!(
+        strings(&tables, "name"),
+        [Some("orders".to_string()), Some("users".to_string())]
+    );
+    assert_eq!(
+        strings(&tables, "schema"),
+        [Some("main".to_string()), Some("main".to_string())]
+    );
+
+    let views = list_views(&conn).unwrap();
+    assert_eq!(strings(&views, "name"), [Some("big_orders".to_string())]);
+    assert_eq!(views.column("ncol").unwrap().i64().unwrap().get(0), Some(3));
+}
+
+#[test]
+fn test_table_schema_maps_dtypes_like_a_read() {
+    let (_tmp, conn) = catalog();
+    let schema = table_schema(&conn, "users").unwrap();
+    assert_eq!(
+        strings(&schema, "name"),
+        ["id", "name", "age", "balance", "email", "misc"].map(|s| Some(s.to_string()))
+    );
+    assert_eq!(
+        strings(&schema, "decl_type")[5],
+        None,
+        "an undeclared column has no declared type"
+    );
+
+    let read = to_dataframe(conn.path().unwrap(), "SELECT * FROM users WHERE 0").unwrap();
+    let expected: Vec<_> = read
+        .dtypes()
+        .iter()
+        .map(|dtype| Some(dtype.to_string()))
+        .collect();
+    assert_eq!(strings(&schema, "dtype"), expected);
+
+    let nullable: Vec<_> = schema
+        .column("nullable")
+        .unwrap()
+        .bool()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(nullable[1], Some(false));
+    assert_eq!(nullable[2], Some(true));
+    assert_eq!(strings(&schema, "default")[1], Some("'anon'".to_string()));
+    assert_eq!(schema.column("pk").unwrap().i64().unwrap().get(0), Some(1));
+
+    let widened = table_schema_with_options(
+        &conn,
+        "users",
+        &ReadOptions {
+            widen_integers: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(strings(&widened, "dtype")[2], Some("i64".to_string()));
+}
+
+#[test]
+fn test_indexes_and_foreign_keys() {
+    let (_tmp, conn) = catalog();
+    let idx = indexes(&conn, "orders").unwrap();
+    assert_eq!(strings(&idx, "name"), [Some("orders_by_user".to_string())]);
+    let columns = idx.column("columns").unwrap().list().unwrap();
+    let columns = columns.get_as_series(0).unwrap();
+    let columns: Vec<_> = columns.str().unwrap().into_iter().collect();
+    assert_eq!(columns, [Some("user_id"), Some("total")]);
+
+    let unique = indexes(&conn, "users").unwrap();
+    assert_eq!(strings(&unique, "origin"), [Some("u".to_string())]);
+
+    let fks = foreign_keys(&conn, "orders").unwrap();
+    assert_eq!(strings(&fks, "from"), [Some("user_id".to_string())]);
+    assert_eq!(strings(&fks, "table"), [Some("users".to_string())]);
+    assert_eq!(strings(&fks, "on_delete"), [Some("CASCADE".to_string())]);
+}
+
+#[test]
+fn test_table_stats() {
+    let (_tmp, conn) = catalog();
+    let stats = table_stats(&conn).unwrap();
+    assert_eq!(
+        strings(&stats, "name"),
+        [Some("orders".to_string()), Some("users".to_string())]
+    );
+    let rows: Vec<_> = stats
+        .column("rows")
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(rows, [Some(1), Some(2)]);
+    let pages = stats.column("pages").unwrap().i64().unwrap();
+    assert!(pages.into_iter().all(|p| p.is_some_and(|p| p > 0)));
+}
+
+#[test]
+fn test_missing_table() {
+    let (_tmp, conn) = catalog();
+    let err = table_schema(&conn, "nope").unwrap_err();
+    assert!(matches!(err, PoliteError::NoSuchTable { .. }));
+}
@@ -0,0 +1,88 @@
+// polite/tests/serialize.rs
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::{deserialize_db, deserialize_db_as, memory_db_path, serialize_db};
+use tempfile::NamedTempFile;
+
+fn users() -> DataFrame {
+    df! {
+        "id" => [1i64, 2, 3],
+        "name" => ["Alice", "Bob", "Carol"],
+    }
+    .unwrap()
+}
+
+#[test]
+fn test_save_to_memory_then_serialize() {
+    let path = memory_db_path("test_save_to_memory_then_serialize");
+    let conn = connect_sqlite(Some(&path)).unwrap();
+    save_dataframe(&path, "users", &users()).unwrap();
+    // Reads open their own connections to the same database
+    assert!(to_dataframe(&path, "SELECT * FROM users")
+        .unwrap()
+        .equals(&users()));
+
+    let bytes = serialize_db(&conn).unwrap();
+    let copy = deserialize_db(&bytes).unwrap();
+    let count: i64 = copy
+        .query_row("SELECT count(*) FROM users", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, 3);
+
+    // The copy is writable and independent of the original
+    from_dataframe(&copy, "users", &users()).unwrap();
+    let original: i64 = conn
+        .query_row("SELECT count(*) FROM users", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(original, 3);
+}
+
+#[test]
+fn test_deserialize_then_read() {
+    let conn = connect_sqlite(None).unwrap();
+    from_dataframe(&conn, "users", &users()).unwrap();
+    let bytes = serialize_db(&conn).unwrap();
+
+    let name = "test_deserialize_then_read";
+    let _db = deserialize_db_as(name, &bytes).unwrap();
+    let df = to_dataframe(&memory_db_path(name), "SELECT * FROM users ORDER BY id").unwrap();
+    assert!(df.equals(&users()));
+}
+
+#[test]
+fn test_memory_names_are_escaped() {
+    let conn = connect_sqlite(None).unwrap();
+    from_dataframe(&conn, "users", &users()).unwrap();
+    let bytes = serialize_db(&conn).unwrap();
+
+    // Unescaped, `?` would start the query, `#` a fragment and `%3f` decode to `?`
+    let name = "test_memory_names_are_escaped?mode=ro#x%3f";
+    let _db = deserialize_db_as(name, &bytes).unwrap();
+    let path = memory_db_path(name);
+    assert!(path.ends_with("?vfs=memdb"));
+    let df = to_dataframe(&path, "SELECT * FROM users ORDER BY id").unwrap();
+    assert!(df.equals(&users()));
+
+    // A different name isn't the same database
+    let other = memory_db_path("test_memory_names_are_escaped?mode=ro#x?");
+    let err = to_dataframe(&other, "SELECT * FROM users").unwrap_err();
+    assert!(err.to_string().contains("no such table: users"), "{err}");
+}
+
+#[test]
+fn test_file_in_wal_mode_round_trips() {
+    let tmp = NamedTempFile::new().unwrap();
+    let conn = connect_sqlite(tmp.path().to_str()).unwrap();
+    conn.execute_batch("PRAGMA journal_mode = WAL").unwrap();
+    from_dataframe(&conn, "users", &users()).unwrap();
+
+    let copy = deserialize_db(&serialize_db(&conn).unwrap()).unwrap();
+    let names: Vec<String> = copy
+        .prepare("SELECT name FROM users ORDER BY id")
+        .unwrap()
+        .query_map([], |r| r.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(names, ["Alice", "Bob", "Carol"]);
+}
@@ -0,0 +1,141 @@
+// polite/tests/session.rs
+#![cfg(feature = "session")]
+mod common;
+
+use common::strings;
+use polars::prelude::*;
+use polite::prelude::*;
+use polite::session::{ChangeCapture, Changeset, OnConflict};
+
+fn with_key() -> WriteOptions {
+    WriteOptions {
+        primary_key: vec!["id".into()],
+        ..Default::default()
+    }
+}
+
+fn users(conn: &rusqlite::Connection) {
+    let df = df! {
+        "id" => [1i64, 2, 3],
+        "name" => ["Alice", "Bob", "Carol"],
+    }
+    .unwrap();
+    from_dataframe_with_options(conn, "users", &df, &with_key()).unwrap();
+}
+
+#[test]
+fn test_changes_as_dataframe() {
+    let conn = connect_sqlite(None).unwrap();
+    users(&conn);
+    let mut capture = ChangeCapture::new(&conn).unwrap();
+    capture.attach("users").unwrap();
+    assert!(capture.is_empty());
+
+    let more = df! { "id" => [4i64], "name" => ["Dan"] }.unwrap();
+    from_dataframe_with_options(&conn, "users", &more, &with_key()).unwrap();
+    conn.execute_batch(
+        "UPDATE users SET name = 'Bobby' WHERE id = 2; DELETE FROM users WHERE id = 3;",
+    )
+    .unwrap();
+
+    let changes = capture.changes().unwrap();
+    let ops = strings(&changes, "op");
+    let at = |op: &str| ops.iter().position(|o| o.as_deref() == Some(op)).unwrap();
+    assert_eq!(changes.height(), 3);
+    assert_eq!(
+        strings(&changes, "table"),
+        vec![Some("users".to_string()); 3]
+    );
+
+    let (insert, update, delete) = (at("INSERT"), at("UPDATE"), at("DELETE"));
+    let pk = strings(&changes, "pk");
+    let old = strings(&changes, "old");
+    let new = strings(&changes, "new");
+    assert_eq!(pk[insert].as_deref(), Some(r#"{"id":4}"#));
+    assert_eq!(old[insert], None);
+    assert_eq!(new[insert].as_deref(), Some(r#"{"name":"Dan"}"#));
+    assert_eq!(pk[update].as_deref(), Some(r#"{"id":2}"#));
+    assert_eq!(old[update].as_deref(), Some(r#"{"name":"Bob"}"#));
+    assert_eq!(new[update].as_deref(), Some(r#"{"name":"Bobby"}"#));
+    assert_eq!(old[delete].as_deref(), Some(r#"{"name":"Carol"}"#));
+    assert_eq!(new[delete], None);
+}
+
+#[test]
+fn test_apply_to_another_database() {
+    let source = connect_sqlite(None).unwrap();
+    let replica = connect_sqlite(None).unwrap();
+    users(&source);
+    users(&replica);
+
+    let mut capture = ChangeCapture::new(&source).unwrap();
+    capture.attach_all().unwrap();
+    source
+        .execute_batch(
+            "INSERT INTO users VALUES (4, 'Dan');
+             UPDATE users SET name = 'Bobby' WHERE id = 2;
+             DELETE FROM users WHERE id = 1;",
+        )
+        .unwrap();
+    // Shipped as bytes
+    let changeset = Changeset::from_bytes(capture.changeset().unwrap().into_bytes());
+    changeset.apply(&replica, OnConflict::Abort).unwrap();
+
+    let sql = "SELECT id, name FROM users ORDER BY id";
+    let rows = |conn: &rusqlite::Connection| -> Vec<(i64, String)> {
+        conn.prepare(sql)
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    };
+    assert_eq!(rows(&replica), rows(&source));
+}
+
+#[test]
+fn test_conflict_policies() {
+    let source = connect_sqlite(None).unwrap();
+    users(&source);
+    let mut capture = ChangeCapture::new(&source).unwrap();
+    capture.attach("users").unwrap();
+    source
+        .execute_batch(
+            "UPDATE users SET name = 'Bobby' WHERE id = 2;
+             INSERT INTO users VALUES (4, 'Dan');",
+        )
+        .unwrap();
+    let changeset = capture.changeset().unwrap();
+
+    // The replica's row 2 has drifted, so the update doesn't find the values it expects
+    let replica = || {
+        let conn = connect_sqlite(None).unwrap();
+        users(&conn);
+        conn.execute_batch("UPDATE users SET name = 'Robert' WHERE id = 2")
+            .unwrap();
+        conn
+    };
+    let name = |conn: &rusqlite::Connection, id: i64| -> Option<String> {
+        conn.query_row("SELECT name FROM users WHERE id = ?1", [id], |r| r.get(0))
+            .ok()
+    };
+
+    let aborted = replica();
+    let err = changeset.apply(&aborted, OnConflict::Abort).unwrap_err();
+    assert!(
+        matches!(&err, PoliteError::ChangesetConflict { table, .. } if table == "users"),
+        "{err}"
+    );
+    // Nothing was applied
+    assert_eq!(name(&aborted, 4), None);
+
+    let omitted = replica();
+    changeset.apply(&omitted, OnConflict::Omit).unwrap();
+    assert_eq!(name(&omitted, 2).as_deref(), Some("Robert"));
+    assert_eq!(name(&omitted, 4).as_deref(), Some("Dan"));
+
+    let replaced = replica();
+    changeset.apply(&replaced, OnConflict::Replace).unwrap();
+    assert_eq!(name(&replaced, 2).as_deref(), Some("Bobby"));
+    assert_eq!(name(&replaced, 4).as_deref(), Some("Dan"));
+}
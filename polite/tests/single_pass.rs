@@ -0,0 +1,88 @@
+// polite/tests/single_pass.rs
+mod common;
+
+use polite::functions::{FunctionFlags, FunctionRegistry};
+use polite::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+
+fn db(rows: usize) -> NamedTempFile {
+    common::db(&format!(
+        "CREATE TABLE t (id INTEGER, grp INTEGER, score REAL, note TEXT);
+         WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < {rows})
+         INSERT INTO t SELECT i, i % 100, i * 0.5, CASE WHEN i > 2 THEN 'n' || i END FROM n;"
+    ))
+}
+
+/// Options whose `tick()` SQL function counts how many times it is evaluated.
+fn counting(single_pass: bool) -> (ReadOptions, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let functions =
+        FunctionRegistry::new().scalar("tick", 0, FunctionFlags::SQLITE_UTF8, move |_| {
+            Ok(counter.fetch_add(1, Ordering::SeqCst) as i64)
+        });
+    let options = ReadOptions {
+        single_pass,
+        connect: ConnectOptions::default().functions(functions),
+        ..Default::default()
+    };
+    (options, calls)
+}
+
+#[test]
+fn test_single_pass_matches_default_read() {
+    let tmp = db(500);
+    let db_path = tmp.path().to_str().unwrap();
+    let sql = "SELECT grp, COUNT(*) AS n, SUM(score) AS total, MAX(note) AS note \
+               FROM t GROUP BY grp ORDER BY grp";
+    let expected = to_dataframe(db_path, sql).unwrap();
+    let options = ReadOptions {
+        single_pass: true,
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(db_path, sql, &options).unwrap();
+    assert!(df.equals_missing(&expected));
+    assert_eq!(df.schema(), expected.schema());
+}
+
+#[test]
+fn test_single_pass_runs_the_query_once() {
+    let tmp = db(200);
+    let db_path = tmp.path().to_str().unwrap();
+    let sql = "SELECT grp, SUM(tick()) AS ticks FROM t GROUP BY grp";
+
+    let (options, calls) = counting(false);
+    to_dataframe_with_options(db_path, sql, &options).unwrap();
+    assert!(
+        calls.load(Ordering::SeqCst) > 200,
+        "the probe runs the query too"
+    );
+
+    let (options, calls) = counting(true);
+    let df = to_dataframe_with_options(db_path, sql, &options).unwrap();
+    assert_eq!(df.height(), 100);
+    assert_eq!(calls.load(Ordering::SeqCst), 200);
+}
+
+#[test]
+fn test_single_pass_types_columns_null_in_the_first_row() {
+    let tmp = db(10);
+    let db_path = tmp.path().to_str().unwrap();
+    let sql = "SELECT id, upper(note) AS note FROM t ORDER BY id";
+    let options = ReadOptions {
+        single_pass: true,
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(db_path, sql, &options).unwrap();
+    assert_eq!(df.height(), 10);
+    let note = df.column("note").unwrap().str().unwrap();
+    assert_eq!(note.get(0), None);
+    assert_eq!(note.get(9), Some("N10"));
+
+    let empty =
+        to_dataframe_with_options(db_path, "SELECT id FROM t WHERE id < 0", &options).unwrap();
+    assert_eq!(empty.height(), 0);
+    assert_eq!(empty.get_column_names(), ["id"]);
+}
@@ -0,0 +1,78 @@
+// polite/tests/threads.rs
+mod common;
+
+use polite::functions::{FunctionFlags, FunctionRegistry};
+use polite::prelude::*;
+use polite::{AutoPartition, ReadThreads};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use tempfile::NamedTempFile;
+
+fn db() -> NamedTempFile {
+    common::db(
+        "CREATE TABLE t (id INTEGER PRIMARY KEY, score REAL);
+         WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000)
+         INSERT INTO t SELECT i, i * 0.5 FROM n;",
+    )
+}
+
+#[test]
+fn test_thread_count_matches_default_read() {
+    let tmp = db();
+    let db_path = tmp.path().to_str().unwrap();
+    let sql = "SELECT * FROM t";
+    let expected = to_dataframe(db_path, sql).unwrap();
+
+    // Each read builds its own pool, so repeating one never trips over rayon's global pool
+    for _ in 0..3 {
+        let options = ReadOptions {
+            auto_partition: AutoPartition::Ranges(4),
+            threads: ReadThreads::Count(2),
+            ..Default::default()
+        };
+        let mut df = to_dataframe_with_options(db_path, sql, &options).unwrap();
+        df.sort_in_place(["id"], Default::default()).unwrap();
+        assert!(df.equals_missing(&expected));
+    }
+}
+
+#[test]
+fn test_partitions_run_on_the_callers_pool() {
+    let tmp = db();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .thread_name(|i| format!("reader-{i}"))
+        .build()
+        .unwrap();
+
+    // `whoami()` records the thread each partition's query is evaluated on
+    let threads = Arc::new(Mutex::new(BTreeSet::new()));
+    let seen = Arc::clone(&threads);
+    let functions =
+        FunctionRegistry::new().scalar("whoami", 0, FunctionFlags::SQLITE_UTF8, move |_| {
+            let name = std::thread::current().name().unwrap_or("").to_string();
+            seen.lock().unwrap().insert(name);
+            Ok(1i64)
+        });
+    let options = ReadOptions {
+        single_pass: true,
+        auto_partition: AutoPartition::Ranges(4),
+        threads: ReadThreads::Pool(Arc::new(pool)),
+        connect: ConnectOptions::default().functions(functions),
+        ..Default::default()
+    };
+    let df = to_dataframe_with_options(
+        tmp.path().to_str().unwrap(),
+        "SELECT id, whoami() AS w FROM t",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(df.height(), 1000);
+
+    let threads = threads.lock().unwrap();
+    assert!(!threads.is_empty());
+    assert!(
+        threads.iter().all(|name| name.starts_with("reader-")),
+        "{threads:?}"
+    );
+}
@@ -0,0 +1,118 @@
+// polite/tests/verbatim.rs
+mod common;
+
+use polite::prelude::*;
+use polite::progress::{Phase, ProgressObserver};
+use std::sync::{Arc, Mutex};
+use tempfile::NamedTempFile;
+
+fn db() -> NamedTempFile {
+    common::db(
+        "CREATE TABLE t (id INTEGER PRIMARY KEY, grp TEXT, score REAL, doc TEXT);
+         INSERT INTO t VALUES (1, 'a', 1.5, '[1, 2]'), (2, 'a', 2.5, '[3]'), (3, 'b', 4.0, '[]');",
+    )
+}
+
+fn read(tmp: &NamedTempFile, sql: &str) -> polars::prelude::DataFrame {
+    to_dataframe(tmp.path().to_str().unwrap(), sql).unwrap_or_else(|e| panic!("{sql} failed: {e}"))
+}
+
+#[test]
+fn test_pragma_and_values() {
+    let tmp = db();
+    let info = read(&tmp, "PRAGMA table_info(t)");
+    assert_eq!(info.height(), 4);
+    let names = info.column("name").unwrap().str().unwrap();
+    assert_eq!(names.get(3), Some("doc"));
+    // `dflt_value` is NULL in every row
+    assert_eq!(info.column("dflt_value").unwrap().null_count(), 4);
+
+    let values = read(&tmp, "VALUES (1, 'x'), (2, 'y')");
+    assert_eq!(values.shape(), (2, 2));
+}
+
+#[test]
+fn test_table_valued_functions_and_windows() {
+    let tmp = db();
+    let each = read(
+        &tmp,
+        "SELECT t.id, j.value FROM t, json_each(t.doc) AS j ORDER BY t.id, j.key",
+    );
+    let value = each.column("value").unwrap().i64().unwrap();
+    assert_eq!(
+        value.into_iter().collect::<Vec<_>>(),
+        [Some(1), Some(2), Some(3)]
+    );
+
+    let windowed = read(
+        &tmp,
+        "SELECT id, sum(score) OVER win AS running FROM t \
+         WINDOW win AS (PARTITION BY grp ORDER BY id ROWS UNBOUNDED PRECEDING) ORDER BY id",
+    );
+    let running = windowed.column("running").unwrap().f64().unwrap();
+    assert_eq!(running.get(1), Some(4.0));
+
+    let recursive = read(
+        &tmp,
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n LIMIT 5) \
+         SELECT i FROM n",
+    );
+    assert_eq!(recursive.height(), 5);
+}
+
+#[test]
+fn test_returning_runs_once() {
+    let tmp = db();
+    let inserted = read(
+        &tmp,
+        "INSERT INTO t (grp, score) VALUES ('c', 9.0), ('c', 10.0) RETURNING id, grp",
+    );
+    assert_eq!(inserted.height(), 2);
+    let ids = inserted.column("id").unwrap().i64().unwrap();
+    assert_eq!(ids.into_iter().collect::<Vec<_>>(), [Some(4), Some(5)]);
+    assert_eq!(read(&tmp, "SELECT * FROM t").height(), 5);
+}
+
+/// Asks for each partition's total, which runs the query again as a `COUNT(*)`.
+#[derive(Default)]
+struct Totals(Mutex<Vec<Option<usize>>>);
+
+impl ProgressObserver for Totals {
+    fn phase(&self, _phase: Phase) {}
+
+    fn rows_fetched(&self, _partition: usize, _rows: usize, total: Option<usize>) {
+        self.0.lock().unwrap().push(total);
+    }
+
+    fn rows_written(&self, _table: &str, _rows: usize, _total: usize) {}
+
+    fn wants_totals(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_queries_whose_count_cant_be_rewritten_run_once() {
+    let tmp = db();
+    let db_path = tmp.path().to_str().unwrap();
+    let totals = Arc::new(Totals::default());
+    let options = ReadOptions {
+        progress: Some(totals.clone()),
+        ..Default::default()
+    };
+
+    // sqlparser parses a compound query, but it can't be wrapped in a count
+    let df = to_dataframe_with_options(
+        db_path,
+        "SELECT id FROM t WHERE grp = 'a' UNION ALL SELECT id FROM t ORDER BY id",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(df.height(), 5);
+    assert!(totals.0.lock().unwrap().iter().all(Option::is_none));
+
+    // A plain query is still counted
+    let df = to_dataframe_with_options(db_path, "SELECT id FROM t", &options).unwrap();
+    assert_eq!(df.height(), 3);
+    assert_eq!(totals.0.lock().unwrap().last(), Some(&Some(3)));
+}